@@ -1,10 +1,11 @@
-use ash::{extensions::khr, prelude::*, vk, Entry, Instance};
+use ash::{
+    extensions::{ext, khr},
+    prelude::*,
+    vk, Entry, Instance,
+};
 use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
 use std::ffi::CStr;
 
-#[cfg(any(target_os = "macos", target_os = "ios"))]
-use ash::extensions::ext; // portability extensions
-
 /// Create a surface from a raw surface handle.
 ///
 /// `instance` must have created with platform specific surface extensions enabled.
@@ -166,3 +167,30 @@ pub fn enumerate_required_extensions(
 
     Ok(extensions)
 }
+
+/// Create a headless surface that is not backed by any window or display.
+///
+/// Presenting to a headless surface has no visible effect, which allows swapchain code to run
+/// without a windowing system, e.g. in CI with a software driver. `instance` must have been
+/// created with the extensions returned by [`enumerate_required_headless_extensions()`].
+///
+/// # Safety
+///
+/// In order for the created [`vk::SurfaceKHR`] to be valid for the duration of its
+/// usage, the [`Instance`] this was called on must be dropped later than the
+/// resulting [`vk::SurfaceKHR`].
+pub unsafe fn create_headless_surface(
+    entry: &Entry,
+    instance: &Instance,
+    allocation_callbacks: Option<&vk::AllocationCallbacks>,
+) -> VkResult<vk::SurfaceKHR> {
+    let surface_desc = vk::HeadlessSurfaceCreateInfoEXT::default();
+    let surface_fn = ext::HeadlessSurface::new(entry, instance);
+    surface_fn.create_headless_surface(&surface_desc, allocation_callbacks)
+}
+
+/// Query the required instance extensions for creating a headless surface with
+/// [`create_headless_surface()`].
+pub fn enumerate_required_headless_extensions() -> Vec<&'static CStr> {
+    vec![khr::Surface::name(), ext::HeadlessSurface::name()]
+}
@@ -0,0 +1,883 @@
+//! Memory type selection and sub-allocation on top of [`Device::allocate_memory()`].
+//!
+//! [`Allocator`] carves [`vk::DeviceMemory`] blocks into smaller allocations using a buddy
+//! strategy. Blocks of host-visible memory types are mapped once when they are created and stay
+//! mapped until they are freed, so [`Allocation::mapped_ptr()`] is always available for them.
+//!
+//! Buddy blocks are always aligned to (and a multiple of) their own size, and the smallest block
+//! is at least [`vk::PhysicalDeviceLimits::non_coherent_atom_size`] bytes large. This means that
+//! flushing an allocation never touches memory belonging to another allocation, and that linear
+//! and optimal resources only need to be kept in separate blocks when
+//! [`vk::PhysicalDeviceLimits::buffer_image_granularity`] exceeds the smallest block size.
+
+use crate::prelude::*;
+use crate::vk;
+use crate::{Device, Instance};
+use std::collections::{BTreeSet, HashMap};
+use std::os::raw::c_void;
+use std::ptr::NonNull;
+
+/// Smallest sub-allocation handed out by a block, before raising it to the atom size.
+const MIN_BLOCK_SIZE: vk::DeviceSize = 256;
+/// Default size of the [`vk::DeviceMemory`] blocks that are sub-allocated.
+const DEFAULT_BLOCK_SIZE: vk::DeviceSize = 256 * 1024 * 1024;
+
+/// Intended access pattern of an allocation, used to pick a memory type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MemoryUsage {
+    /// Only accessed by the device, e.g. render targets and sampled images.
+    GpuOnly,
+    /// Written by the host and read by the device, e.g. uniform and staging buffers.
+    CpuToGpu,
+    /// Written by the device and read back by the host.
+    GpuToCpu,
+    /// Only accessed by the host; prefers memory that is not device-local.
+    CpuOnly,
+}
+
+impl MemoryUsage {
+    fn required_flags(self) -> vk::MemoryPropertyFlags {
+        match self {
+            Self::GpuOnly => vk::MemoryPropertyFlags::empty(),
+            Self::CpuToGpu | Self::GpuToCpu => vk::MemoryPropertyFlags::HOST_VISIBLE,
+            Self::CpuOnly => {
+                vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT
+            }
+        }
+    }
+
+    fn preferred_flags(self) -> vk::MemoryPropertyFlags {
+        match self {
+            Self::GpuOnly | Self::CpuToGpu => vk::MemoryPropertyFlags::DEVICE_LOCAL,
+            Self::GpuToCpu => vk::MemoryPropertyFlags::HOST_CACHED,
+            Self::CpuOnly => vk::MemoryPropertyFlags::empty(),
+        }
+    }
+
+    fn unwanted_flags(self) -> vk::MemoryPropertyFlags {
+        match self {
+            Self::GpuOnly => vk::MemoryPropertyFlags::HOST_VISIBLE,
+            Self::CpuToGpu => vk::MemoryPropertyFlags::HOST_CACHED,
+            Self::GpuToCpu => vk::MemoryPropertyFlags::empty(),
+            Self::CpuOnly => vk::MemoryPropertyFlags::DEVICE_LOCAL,
+        }
+    }
+}
+
+/// Returns all memory types allowed by `memory_type_bits` that satisfy the requirements of
+/// `usage`, best match first.
+pub fn memory_type_candidates(
+    memory_properties: &vk::PhysicalDeviceMemoryProperties,
+    memory_type_bits: u32,
+    usage: MemoryUsage,
+) -> Vec<u32> {
+    let required = usage.required_flags();
+    let preferred = usage.preferred_flags();
+    let unwanted = usage.unwanted_flags();
+    let mut candidates = memory_properties.memory_types
+        [..memory_properties.memory_type_count as usize]
+        .iter()
+        .enumerate()
+        .filter(|(index, memory_type)| {
+            (1 << index) & memory_type_bits != 0 && memory_type.property_flags.contains(required)
+        })
+        .map(|(index, memory_type)| {
+            let flags = memory_type.property_flags;
+            let cost = (preferred & !flags).as_raw().count_ones()
+                + (unwanted & flags).as_raw().count_ones();
+            (cost, index as u32)
+        })
+        .collect::<Vec<_>>();
+    // Stable sort: ties keep the driver's ordering, which is sorted by preference already.
+    candidates.sort_by_key(|&(cost, _)| cost);
+    candidates.into_iter().map(|(_, index)| index).collect()
+}
+
+/// Finds the memory type best suited for `usage` out of those allowed by `memory_type_bits`.
+///
+/// # Examples
+/// ```
+/// # use ash::{alloc, vk};
+/// let mut memory_properties = vk::PhysicalDeviceMemoryProperties::default();
+/// memory_properties.memory_type_count = 2;
+/// memory_properties.memory_types[0].property_flags = vk::MemoryPropertyFlags::DEVICE_LOCAL;
+/// memory_properties.memory_types[1].property_flags =
+///     vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT;
+/// let index = alloc::find_memory_type_index(&memory_properties, !0, alloc::MemoryUsage::CpuToGpu);
+/// assert_eq!(index, Some(1));
+/// ```
+pub fn find_memory_type_index(
+    memory_properties: &vk::PhysicalDeviceMemoryProperties,
+    memory_type_bits: u32,
+    usage: MemoryUsage,
+) -> Option<u32> {
+    memory_type_candidates(memory_properties, memory_type_bits, usage)
+        .first()
+        .copied()
+}
+
+/// Memory requirements of a resource together with its dedicated allocation preference, as
+/// reported through [`vk::MemoryDedicatedRequirements`].
+#[derive(Clone, Copy, Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct MemoryRequirements {
+    pub requirements: vk::MemoryRequirements,
+    pub prefers_dedicated_allocation: bool,
+    pub requires_dedicated_allocation: bool,
+}
+
+impl MemoryRequirements {
+    /// Queries the requirements of `buffer`. Requires Vulkan 1.1.
+    pub unsafe fn for_buffer(device: &Device, buffer: vk::Buffer) -> Self {
+        let info = vk::BufferMemoryRequirementsInfo2::builder().buffer(buffer);
+        let mut dedicated = vk::MemoryDedicatedRequirements::default();
+        let mut requirements = vk::MemoryRequirements2::builder().push_next(&mut dedicated);
+        device.get_buffer_memory_requirements2(&info, &mut requirements);
+        Self {
+            requirements: requirements.memory_requirements,
+            prefers_dedicated_allocation: dedicated.prefers_dedicated_allocation == vk::TRUE,
+            requires_dedicated_allocation: dedicated.requires_dedicated_allocation == vk::TRUE,
+        }
+    }
+
+    /// Queries the requirements of `image`. Requires Vulkan 1.1.
+    pub unsafe fn for_image(device: &Device, image: vk::Image) -> Self {
+        let info = vk::ImageMemoryRequirementsInfo2::builder().image(image);
+        let mut dedicated = vk::MemoryDedicatedRequirements::default();
+        let mut requirements = vk::MemoryRequirements2::builder().push_next(&mut dedicated);
+        device.get_image_memory_requirements2(&info, &mut requirements);
+        Self {
+            requirements: requirements.memory_requirements,
+            prefers_dedicated_allocation: dedicated.prefers_dedicated_allocation == vk::TRUE,
+            requires_dedicated_allocation: dedicated.requires_dedicated_allocation == vk::TRUE,
+        }
+    }
+}
+
+/// The resource a dedicated allocation is made for, chained as [`vk::MemoryDedicatedAllocateInfo`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DedicatedAllocation {
+    Buffer(vk::Buffer),
+    Image(vk::Image),
+}
+
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct AllocationCreateDesc {
+    pub requirements: vk::MemoryRequirements,
+    pub usage: MemoryUsage,
+    /// `true` for buffers and linearly tiled images, `false` for optimally tiled images.
+    pub linear: bool,
+    /// Allocate a separate [`vk::DeviceMemory`] bound to this resource instead of sub-allocating.
+    pub dedicated: Option<DedicatedAllocation>,
+}
+
+pub struct AllocatorCreateDesc {
+    pub instance: Instance,
+    pub device: Device,
+    pub physical_device: vk::PhysicalDevice,
+    /// Size of the blocks that are sub-allocated, rounded up to a power of two. Defaults to
+    /// 256 MiB, capped to an eighth of the heap size for small heaps.
+    pub block_size: Option<vk::DeviceSize>,
+    /// Consult [`vk::PhysicalDeviceMemoryBudgetPropertiesEXT`] before allocating new blocks.
+    /// Requires `VK_EXT_memory_budget` to be enabled on the device.
+    pub memory_budget: bool,
+    /// Allocate all memory with [`vk::MemoryAllocateFlags::DEVICE_ADDRESS`], so that buffers
+    /// created with [`vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS`] can be bound to it. Requires
+    /// the `bufferDeviceAddress` feature to be enabled on the device.
+    pub buffer_device_address: bool,
+}
+
+/// Budget and usage of a single memory heap.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HeapBudget {
+    /// How much memory the process can use from this heap before allocations may fail or
+    /// cause performance degradation. Equal to the heap size without `VK_EXT_memory_budget`.
+    pub budget: vk::DeviceSize,
+    /// Memory currently in use by the process, or by this allocator without
+    /// `VK_EXT_memory_budget`.
+    pub usage: vk::DeviceSize,
+    /// Memory allocated from this heap through this allocator.
+    pub allocated: vk::DeviceSize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AllocationKind {
+    Dedicated,
+    Block { pool: usize, block: usize },
+}
+
+/// A region of device memory handed out by [`Allocator`].
+///
+/// Allocations must be returned through [`Allocator::free()`].
+#[derive(Debug)]
+pub struct Allocation {
+    memory: vk::DeviceMemory,
+    offset: vk::DeviceSize,
+    size: vk::DeviceSize,
+    memory_type_index: u32,
+    mapped_ptr: Option<NonNull<c_void>>,
+    kind: AllocationKind,
+}
+
+unsafe impl Send for Allocation {}
+unsafe impl Sync for Allocation {}
+
+impl Allocation {
+    pub fn memory(&self) -> vk::DeviceMemory {
+        self.memory
+    }
+
+    pub fn offset(&self) -> vk::DeviceSize {
+        self.offset
+    }
+
+    pub fn size(&self) -> vk::DeviceSize {
+        self.size
+    }
+
+    pub fn memory_type_index(&self) -> u32 {
+        self.memory_type_index
+    }
+
+    /// Host address of the start of this allocation if its memory type is host-visible.
+    pub fn mapped_ptr(&self) -> Option<NonNull<c_void>> {
+        self.mapped_ptr
+    }
+
+    pub fn is_dedicated(&self) -> bool {
+        self.kind == AllocationKind::Dedicated
+    }
+}
+
+/// Power-of-two buddy allocator managing offsets within a single block.
+#[derive(Debug)]
+struct BuddyAllocator {
+    min_size: vk::DeviceSize,
+    /// Free offsets per order, where order `n` has size `min_size << n`.
+    free_lists: Vec<BTreeSet<vk::DeviceSize>>,
+    /// Order of every live allocation, keyed by offset.
+    allocated: HashMap<vk::DeviceSize, usize>,
+}
+
+impl BuddyAllocator {
+    fn new(size: vk::DeviceSize, min_size: vk::DeviceSize) -> Self {
+        assert!(size.is_power_of_two() && min_size.is_power_of_two() && min_size <= size);
+        let orders = (size / min_size).trailing_zeros() as usize + 1;
+        let mut free_lists = vec![BTreeSet::new(); orders];
+        free_lists[orders - 1].insert(0);
+        Self {
+            min_size,
+            free_lists,
+            allocated: HashMap::new(),
+        }
+    }
+
+    /// Size of the block of the given order.
+    fn order_size(&self, order: usize) -> vk::DeviceSize {
+        self.min_size << order
+    }
+
+    /// Order of the smallest block that fits `size` bytes at `alignment`, if any.
+    fn order_for(&self, size: vk::DeviceSize, alignment: vk::DeviceSize) -> Option<usize> {
+        let size = size
+            .max(alignment)
+            .max(self.min_size)
+            .checked_next_power_of_two()?;
+        let order = (size / self.min_size).trailing_zeros() as usize;
+        if order < self.free_lists.len() {
+            Some(order)
+        } else {
+            None
+        }
+    }
+
+    fn alloc(&mut self, size: vk::DeviceSize, alignment: vk::DeviceSize) -> Option<vk::DeviceSize> {
+        let order = self.order_for(size, alignment)?;
+        let mut current =
+            (order..self.free_lists.len()).find(|&o| !self.free_lists[o].is_empty())?;
+        let offset = *self.free_lists[current].iter().next().unwrap();
+        self.free_lists[current].remove(&offset);
+        // Split the block, returning the upper halves to the free lists.
+        while current > order {
+            current -= 1;
+            let buddy = offset + self.order_size(current);
+            self.free_lists[current].insert(buddy);
+        }
+        self.allocated.insert(offset, order);
+        Some(offset)
+    }
+
+    fn free(&mut self, offset: vk::DeviceSize) {
+        let mut order = self
+            .allocated
+            .remove(&offset)
+            .expect("offset was not allocated from this block");
+        let mut offset = offset;
+        while order + 1 < self.free_lists.len() {
+            let buddy = offset ^ self.order_size(order);
+            if !self.free_lists[order].remove(&buddy) {
+                break;
+            }
+            offset = offset.min(buddy);
+            order += 1;
+        }
+        self.free_lists[order].insert(offset);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.allocated.is_empty()
+    }
+}
+
+struct MemoryBlock {
+    memory: vk::DeviceMemory,
+    size: vk::DeviceSize,
+    mapped_ptr: Option<NonNull<c_void>>,
+    buddy: BuddyAllocator,
+}
+
+/// All blocks of one memory type holding one kind of resource.
+struct MemoryPool {
+    memory_type_index: u32,
+    linear: bool,
+    blocks: Vec<Option<MemoryBlock>>,
+}
+
+/// Sub-allocating device memory allocator.
+///
+/// The allocator does not implement [`Drop`]; call [`Allocator::destroy()`] once all
+/// allocations have been freed.
+pub struct Allocator {
+    instance: Instance,
+    device: Device,
+    physical_device: vk::PhysicalDevice,
+    memory_properties: vk::PhysicalDeviceMemoryProperties,
+    buffer_image_granularity: vk::DeviceSize,
+    non_coherent_atom_size: vk::DeviceSize,
+    min_block_size: vk::DeviceSize,
+    block_size: Option<vk::DeviceSize>,
+    memory_budget: bool,
+    buffer_device_address: bool,
+    pools: Vec<MemoryPool>,
+    heap_allocated: [vk::DeviceSize; vk::MAX_MEMORY_HEAPS],
+}
+
+unsafe impl Send for Allocator {}
+unsafe impl Sync for Allocator {}
+
+impl Allocator {
+    pub unsafe fn new(desc: AllocatorCreateDesc) -> Self {
+        let memory_properties = desc
+            .instance
+            .get_physical_device_memory_properties(desc.physical_device);
+        let limits = desc
+            .instance
+            .get_physical_device_properties(desc.physical_device)
+            .limits;
+        let non_coherent_atom_size = limits.non_coherent_atom_size.max(1);
+        Self {
+            instance: desc.instance,
+            device: desc.device,
+            physical_device: desc.physical_device,
+            memory_properties,
+            buffer_image_granularity: limits.buffer_image_granularity.max(1),
+            non_coherent_atom_size,
+            min_block_size: MIN_BLOCK_SIZE.max(non_coherent_atom_size.next_power_of_two()),
+            block_size: desc.block_size.map(|size| size.next_power_of_two()),
+            memory_budget: desc.memory_budget,
+            buffer_device_address: desc.buffer_device_address,
+            pools: Vec::new(),
+            heap_allocated: [0; vk::MAX_MEMORY_HEAPS],
+        }
+    }
+
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    pub fn memory_properties(&self) -> &vk::PhysicalDeviceMemoryProperties {
+        &self.memory_properties
+    }
+
+    fn memory_type(&self, memory_type_index: u32) -> vk::MemoryType {
+        self.memory_properties.memory_types[memory_type_index as usize]
+    }
+
+    fn block_size_for(&self, memory_type_index: u32) -> vk::DeviceSize {
+        let heap_index = self.memory_type(memory_type_index).heap_index as usize;
+        let heap_size = self.memory_properties.memory_heaps[heap_index].size;
+        let default = if heap_size <= 1024 * 1024 * 1024 {
+            (heap_size / 8).next_power_of_two()
+        } else {
+            DEFAULT_BLOCK_SIZE
+        };
+        self.block_size.unwrap_or(default).max(self.min_block_size)
+    }
+
+    /// Queries the budget of every memory heap, see [`HeapBudget`].
+    pub unsafe fn heap_budgets(&self) -> Vec<HeapBudget> {
+        let heap_count = self.memory_properties.memory_heap_count as usize;
+        let heaps = &self.memory_properties.memory_heaps[..heap_count];
+        if self.memory_budget {
+            let mut budget = vk::PhysicalDeviceMemoryBudgetPropertiesEXT::default();
+            let mut properties =
+                vk::PhysicalDeviceMemoryProperties2::builder().push_next(&mut budget);
+            self.instance
+                .get_physical_device_memory_properties2(self.physical_device, &mut properties);
+            (0..heap_count)
+                .map(|i| HeapBudget {
+                    budget: budget.heap_budget[i],
+                    usage: budget.heap_usage[i],
+                    allocated: self.heap_allocated[i],
+                })
+                .collect()
+        } else {
+            heaps
+                .iter()
+                .zip(&self.heap_allocated)
+                .map(|(heap, &allocated)| HeapBudget {
+                    budget: heap.size,
+                    usage: allocated,
+                    allocated,
+                })
+                .collect()
+        }
+    }
+
+    /// Allocates and (for host-visible types) maps a new [`vk::DeviceMemory`] object, respecting
+    /// the heap budget.
+    unsafe fn allocate_device_memory(
+        &mut self,
+        memory_type_index: u32,
+        size: vk::DeviceSize,
+        dedicated: Option<DedicatedAllocation>,
+    ) -> VkResult<(vk::DeviceMemory, Option<NonNull<c_void>>)> {
+        let memory_type = self.memory_type(memory_type_index);
+        let heap_index = memory_type.heap_index as usize;
+        if self.memory_budget {
+            let budget = self.heap_budgets()[heap_index];
+            if budget.usage + size > budget.budget {
+                return Err(vk::Result::ERROR_OUT_OF_DEVICE_MEMORY);
+            }
+        }
+
+        let mut dedicated_info = vk::MemoryDedicatedAllocateInfo::default();
+        let mut flags_info =
+            vk::MemoryAllocateFlagsInfo::builder().flags(vk::MemoryAllocateFlags::DEVICE_ADDRESS);
+        let mut allocate_info = vk::MemoryAllocateInfo::builder()
+            .allocation_size(size)
+            .memory_type_index(memory_type_index);
+        if self.buffer_device_address {
+            allocate_info = allocate_info.push_next(&mut flags_info);
+        }
+        if let Some(dedicated) = dedicated {
+            match dedicated {
+                DedicatedAllocation::Buffer(buffer) => dedicated_info.buffer = buffer,
+                DedicatedAllocation::Image(image) => dedicated_info.image = image,
+            }
+            allocate_info = allocate_info.push_next(&mut dedicated_info);
+        }
+        let memory = self.device.allocate_memory(&allocate_info, None)?;
+
+        let mapped_ptr = if memory_type
+            .property_flags
+            .contains(vk::MemoryPropertyFlags::HOST_VISIBLE)
+        {
+            match self
+                .device
+                .map_memory(memory, 0, vk::WHOLE_SIZE, vk::MemoryMapFlags::empty())
+            {
+                Ok(ptr) => NonNull::new(ptr),
+                Err(err) => {
+                    self.device.free_memory(memory, None);
+                    return Err(err);
+                }
+            }
+        } else {
+            None
+        };
+
+        self.heap_allocated[heap_index] += size;
+        Ok((memory, mapped_ptr))
+    }
+
+    unsafe fn free_device_memory(
+        &mut self,
+        memory: vk::DeviceMemory,
+        memory_type_index: u32,
+        size: vk::DeviceSize,
+    ) {
+        let heap_index = self.memory_type(memory_type_index).heap_index as usize;
+        self.heap_allocated[heap_index] -= size;
+        // Freeing implicitly unmaps the memory.
+        self.device.free_memory(memory, None);
+    }
+
+    unsafe fn allocate_from_pools(
+        &mut self,
+        memory_type_index: u32,
+        requirements: &vk::MemoryRequirements,
+        linear: bool,
+    ) -> VkResult<Allocation> {
+        // Linear and optimal resources can only share a block when every buddy block is at least
+        // as large as a granularity page.
+        let separate = self.buffer_image_granularity > self.min_block_size;
+        let linear = linear && separate;
+        let pool_index = match self.pools.iter().position(|pool| {
+            pool.memory_type_index == memory_type_index && (!separate || pool.linear == linear)
+        }) {
+            Some(index) => index,
+            None => {
+                self.pools.push(MemoryPool {
+                    memory_type_index,
+                    linear,
+                    blocks: Vec::new(),
+                });
+                self.pools.len() - 1
+            }
+        };
+
+        let pool = &mut self.pools[pool_index];
+        for (block_index, block) in pool.blocks.iter_mut().enumerate() {
+            let block = match block {
+                Some(block) => block,
+                None => continue,
+            };
+            if let Some(offset) = block.buddy.alloc(requirements.size, requirements.alignment) {
+                return Ok(Allocation {
+                    memory: block.memory,
+                    offset,
+                    size: requirements.size,
+                    memory_type_index,
+                    mapped_ptr: block
+                        .mapped_ptr
+                        .map(|ptr| NonNull::new_unchecked(ptr.as_ptr().add(offset as usize))),
+                    kind: AllocationKind::Block {
+                        pool: pool_index,
+                        block: block_index,
+                    },
+                });
+            }
+        }
+
+        let block_size = self.block_size_for(memory_type_index);
+        let (memory, mapped_ptr) =
+            self.allocate_device_memory(memory_type_index, block_size, None)?;
+        let mut buddy = BuddyAllocator::new(block_size, self.min_block_size);
+        let offset = buddy
+            .alloc(requirements.size, requirements.alignment)
+            .expect("allocation does not fit in a fresh block");
+        let block = MemoryBlock {
+            memory,
+            size: block_size,
+            mapped_ptr,
+            buddy,
+        };
+        let blocks = &mut self.pools[pool_index].blocks;
+        let block_index = match blocks.iter().position(Option::is_none) {
+            Some(index) => {
+                blocks[index] = Some(block);
+                index
+            }
+            None => {
+                blocks.push(Some(block));
+                blocks.len() - 1
+            }
+        };
+        Ok(Allocation {
+            memory,
+            offset,
+            size: requirements.size,
+            memory_type_index,
+            mapped_ptr: mapped_ptr
+                .map(|ptr| NonNull::new_unchecked(ptr.as_ptr().add(offset as usize))),
+            kind: AllocationKind::Block {
+                pool: pool_index,
+                block: block_index,
+            },
+        })
+    }
+
+    /// Allocates memory matching `desc`, falling back to less suitable memory types when the
+    /// preferred ones are out of memory or budget.
+    pub unsafe fn allocate(&mut self, desc: &AllocationCreateDesc) -> VkResult<Allocation> {
+        let candidates = memory_type_candidates(
+            &self.memory_properties,
+            desc.requirements.memory_type_bits,
+            desc.usage,
+        );
+        if candidates.is_empty() {
+            return Err(vk::Result::ERROR_FEATURE_NOT_PRESENT);
+        }
+
+        let mut result = Err(vk::Result::ERROR_OUT_OF_DEVICE_MEMORY);
+        for memory_type_index in candidates {
+            let block_size = self.block_size_for(memory_type_index);
+            let fits_block = desc.requirements.size.max(desc.requirements.alignment) <= block_size;
+            result = if desc.dedicated.is_some() || !fits_block {
+                self.allocate_device_memory(
+                    memory_type_index,
+                    desc.requirements.size,
+                    desc.dedicated,
+                )
+                .map(|(memory, mapped_ptr)| Allocation {
+                    memory,
+                    offset: 0,
+                    size: desc.requirements.size,
+                    memory_type_index,
+                    mapped_ptr,
+                    kind: AllocationKind::Dedicated,
+                })
+            } else {
+                self.allocate_from_pools(memory_type_index, &desc.requirements, desc.linear)
+            };
+            match result {
+                Err(vk::Result::ERROR_OUT_OF_DEVICE_MEMORY)
+                | Err(vk::Result::ERROR_OUT_OF_HOST_MEMORY) => continue,
+                _ => break,
+            }
+        }
+        result
+    }
+
+    /// Allocates memory for `buffer` and binds it, using a dedicated allocation when the
+    /// implementation prefers or requires one. Requires Vulkan 1.1.
+    pub unsafe fn allocate_for_buffer(
+        &mut self,
+        buffer: vk::Buffer,
+        usage: MemoryUsage,
+    ) -> VkResult<Allocation> {
+        let requirements = MemoryRequirements::for_buffer(&self.device, buffer);
+        let dedicated =
+            requirements.prefers_dedicated_allocation || requirements.requires_dedicated_allocation;
+        let allocation = self.allocate(&AllocationCreateDesc {
+            requirements: requirements.requirements,
+            usage,
+            linear: true,
+            dedicated: if dedicated {
+                Some(DedicatedAllocation::Buffer(buffer))
+            } else {
+                None
+            },
+        })?;
+        if let Err(err) =
+            self.device
+                .bind_buffer_memory(buffer, allocation.memory, allocation.offset)
+        {
+            self.free(allocation);
+            return Err(err);
+        }
+        Ok(allocation)
+    }
+
+    /// Allocates memory for `image` and binds it, using a dedicated allocation when the
+    /// implementation prefers or requires one. Requires Vulkan 1.1.
+    pub unsafe fn allocate_for_image(
+        &mut self,
+        image: vk::Image,
+        tiling: vk::ImageTiling,
+        usage: MemoryUsage,
+    ) -> VkResult<Allocation> {
+        let requirements = MemoryRequirements::for_image(&self.device, image);
+        let dedicated =
+            requirements.prefers_dedicated_allocation || requirements.requires_dedicated_allocation;
+        let allocation = self.allocate(&AllocationCreateDesc {
+            requirements: requirements.requirements,
+            usage,
+            linear: tiling == vk::ImageTiling::LINEAR,
+            dedicated: if dedicated {
+                Some(DedicatedAllocation::Image(image))
+            } else {
+                None
+            },
+        })?;
+        if let Err(err) = self
+            .device
+            .bind_image_memory(image, allocation.memory, allocation.offset)
+        {
+            self.free(allocation);
+            return Err(err);
+        }
+        Ok(allocation)
+    }
+
+    /// Returns `allocation` to its block. Empty blocks are released, except for the last one of
+    /// each pool to avoid thrashing.
+    pub unsafe fn free(&mut self, allocation: Allocation) {
+        match allocation.kind {
+            AllocationKind::Dedicated => self.free_device_memory(
+                allocation.memory,
+                allocation.memory_type_index,
+                allocation.size,
+            ),
+            AllocationKind::Block { pool, block } => {
+                let pool = &mut self.pools[pool];
+                let live_blocks = pool.blocks.iter().filter(|b| b.is_some()).count();
+                let slot = &mut pool.blocks[block];
+                let memory_block = slot.as_mut().expect("allocation from a freed block");
+                memory_block.buddy.free(allocation.offset);
+                if memory_block.buddy.is_empty() && live_blocks > 1 {
+                    let memory_block = slot.take().unwrap();
+                    let memory_type_index = pool.memory_type_index;
+                    self.free_device_memory(
+                        memory_block.memory,
+                        memory_type_index,
+                        memory_block.size,
+                    );
+                }
+            }
+        }
+    }
+
+    fn mapped_range(
+        &self,
+        allocation: &Allocation,
+        offset: vk::DeviceSize,
+        size: vk::DeviceSize,
+    ) -> Option<vk::MappedMemoryRange> {
+        let flags = self
+            .memory_type(allocation.memory_type_index)
+            .property_flags;
+        if !flags.contains(vk::MemoryPropertyFlags::HOST_VISIBLE)
+            || flags.contains(vk::MemoryPropertyFlags::HOST_COHERENT)
+        {
+            return None;
+        }
+        let size = if size == vk::WHOLE_SIZE {
+            allocation.size - offset
+        } else {
+            size
+        };
+        // Block-backed allocations occupy a buddy block that is a multiple of the atom size, so
+        // rounding outwards never touches another allocation. Dedicated allocations that would
+        // be rounded past their end flush up to the end of the memory object instead.
+        let atom = self.non_coherent_atom_size;
+        let start = (allocation.offset + offset) / atom * atom;
+        let end = allocation.offset + offset + size;
+        let end = end + (atom - end % atom) % atom;
+        let size = match allocation.kind {
+            AllocationKind::Dedicated if end > allocation.size => vk::WHOLE_SIZE,
+            _ => end - start,
+        };
+        Some(
+            vk::MappedMemoryRange::builder()
+                .memory(allocation.memory)
+                .offset(start)
+                .size(size)
+                .build(),
+        )
+    }
+
+    /// Flushes host writes to `size` bytes at `offset` within `allocation`. A no-op for
+    /// host-coherent memory.
+    pub unsafe fn flush(
+        &self,
+        allocation: &Allocation,
+        offset: vk::DeviceSize,
+        size: vk::DeviceSize,
+    ) -> VkResult<()> {
+        match self.mapped_range(allocation, offset, size) {
+            Some(range) => self.device.flush_mapped_memory_ranges(&[range]),
+            None => Ok(()),
+        }
+    }
+
+    /// Makes device writes to `size` bytes at `offset` within `allocation` visible to the host.
+    /// A no-op for host-coherent memory.
+    pub unsafe fn invalidate(
+        &self,
+        allocation: &Allocation,
+        offset: vk::DeviceSize,
+        size: vk::DeviceSize,
+    ) -> VkResult<()> {
+        match self.mapped_range(allocation, offset, size) {
+            Some(range) => self.device.invalidate_mapped_memory_ranges(&[range]),
+            None => Ok(()),
+        }
+    }
+
+    /// Frees all blocks owned by the allocator. Outstanding block allocations become invalid;
+    /// dedicated allocations must still be freed through [`Allocator::free()`] beforehand.
+    pub unsafe fn destroy(mut self) {
+        for pool in std::mem::take(&mut self.pools) {
+            for block in pool.blocks.into_iter().flatten() {
+                self.free_device_memory(block.memory, pool.memory_type_index, block.size);
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for Allocator {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Allocator")
+            .field("physical_device", &self.physical_device)
+            .field("min_block_size", &self.min_block_size)
+            .field("pools", &self.pools.len())
+            .field("heap_allocated", &self.heap_allocated)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buddy_split_and_merge() {
+        let mut buddy = BuddyAllocator::new(1024, 256);
+        let a = buddy.alloc(100, 1).unwrap();
+        let b = buddy.alloc(256, 256).unwrap();
+        let c = buddy.alloc(512, 512).unwrap();
+        assert_eq!((a, b, c), (0, 256, 512));
+        assert_eq!(buddy.alloc(1, 1), None);
+        buddy.free(a);
+        buddy.free(b);
+        // The two lower quarters merged back into a 512 byte block.
+        assert_eq!(buddy.alloc(300, 1), Some(0));
+        buddy.free(0);
+        buddy.free(c);
+        assert!(buddy.is_empty());
+        assert_eq!(buddy.alloc(1024, 1), Some(0));
+    }
+
+    #[test]
+    fn buddy_respects_alignment() {
+        let mut buddy = BuddyAllocator::new(4096, 256);
+        let a = buddy.alloc(16, 1).unwrap();
+        let b = buddy.alloc(16, 1024).unwrap();
+        assert_eq!(a, 0);
+        assert_eq!(b % 1024, 0);
+        assert_eq!(buddy.alloc(16, 8192), None);
+    }
+
+    #[test]
+    fn memory_type_preference() {
+        let mut props = vk::PhysicalDeviceMemoryProperties {
+            memory_type_count: 3,
+            ..Default::default()
+        };
+        props.memory_types[0].property_flags =
+            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT;
+        props.memory_types[1].property_flags = vk::MemoryPropertyFlags::DEVICE_LOCAL;
+        props.memory_types[2].property_flags = vk::MemoryPropertyFlags::HOST_VISIBLE
+            | vk::MemoryPropertyFlags::HOST_COHERENT
+            | vk::MemoryPropertyFlags::HOST_CACHED;
+        assert_eq!(
+            find_memory_type_index(&props, !0, MemoryUsage::GpuOnly),
+            Some(1)
+        );
+        assert_eq!(
+            find_memory_type_index(&props, !0, MemoryUsage::GpuToCpu),
+            Some(2)
+        );
+        assert_eq!(
+            find_memory_type_index(&props, 0b011, MemoryUsage::GpuToCpu),
+            Some(0)
+        );
+        assert_eq!(
+            find_memory_type_index(&props, 0b010, MemoryUsage::CpuOnly),
+            None
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! Driving [`vk::DeferredOperationKHR`]s to completion on multiple host threads.
+//!
+//! Deferred operations, such as host acceleration structure builds or ray tracing pipeline
+//! compiles, only make progress on threads that join them. [`complete_deferred_operation()`]
+//! joins an operation from a set of scoped threads and blocks until it completes, while
+//! [`spawn_deferred_operation()`] hands joiners to an existing executor and returns a future
+//! that resolves to the result of the operation.
+
+use crate::extensions::khr::DeferredHostOperations;
+use crate::prelude::*;
+use crate::vk;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+/// Number of joiners worth running for `operation`, capped to `max_joiners`.
+unsafe fn joiner_count(
+    deferred_host_operations: &DeferredHostOperations,
+    operation: vk::DeferredOperationKHR,
+    max_joiners: usize,
+) -> usize {
+    let max_concurrency =
+        deferred_host_operations.get_deferred_operation_max_concurrency(operation);
+    (max_concurrency as usize).min(max_joiners)
+}
+
+/// Joins the calling thread to `operation` until the implementation has no more work for it.
+///
+/// Returns when the operation has completed or when [`vk::Result::THREAD_DONE_KHR`] signals
+/// that the remaining work is being executed by other threads. Joins that return
+/// [`vk::Result::THREAD_IDLE_KHR`] are retried after yielding the thread.
+pub unsafe fn join_deferred_operation(
+    deferred_host_operations: &DeferredHostOperations,
+    operation: vk::DeferredOperationKHR,
+) -> VkResult<()> {
+    loop {
+        let err_code = deferred_host_operations
+            .fp()
+            .deferred_operation_join_khr(deferred_host_operations.device(), operation);
+        match err_code {
+            vk::Result::SUCCESS | vk::Result::THREAD_DONE_KHR => return Ok(()),
+            vk::Result::THREAD_IDLE_KHR => thread::yield_now(),
+            _ => return Err(err_code),
+        }
+    }
+}
+
+/// Joins `operation` from the calling thread and up to `max_threads - 1` scoped threads, bounded
+/// by [`DeferredHostOperations::get_deferred_operation_max_concurrency()`], and returns the
+/// result of the completed operation.
+pub unsafe fn complete_deferred_operation(
+    deferred_host_operations: &DeferredHostOperations,
+    operation: vk::DeferredOperationKHR,
+    max_threads: usize,
+) -> VkResult<()> {
+    let joiners = joiner_count(deferred_host_operations, operation, max_threads).max(1);
+    thread::scope(|scope| {
+        let threads = (1..joiners)
+            .map(|_| {
+                scope.spawn(|| unsafe {
+                    join_deferred_operation(deferred_host_operations, operation)
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut result = join_deferred_operation(deferred_host_operations, operation);
+        for thread in threads {
+            let joined = thread.join().expect("deferred operation joiner panicked");
+            result = result.and(joined);
+        }
+        result
+    })?;
+    deferred_host_operations.get_deferred_operation_result(operation)
+}
+
+#[derive(Default)]
+struct JoinState {
+    running: usize,
+    error: Option<vk::Result>,
+    waker: Option<Waker>,
+}
+
+type Spawn = Box<dyn FnMut(Box<dyn FnOnce() + Send + 'static>) + Send>;
+
+/// A joiner that records its result in `state` and wakes the future once it is the last one
+/// running. The caller accounts for it in [`JoinState::running`] before spawning it.
+fn joiner(
+    deferred_host_operations: DeferredHostOperations,
+    operation: vk::DeferredOperationKHR,
+    state: Arc<Mutex<JoinState>>,
+) -> Box<dyn FnOnce() + Send + 'static> {
+    Box::new(move || {
+        let result = unsafe { join_deferred_operation(&deferred_host_operations, operation) };
+        let mut state = state.lock().unwrap();
+        if let Err(err) = result {
+            state.error.get_or_insert(err);
+        }
+        state.running -= 1;
+        if state.running == 0 {
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        }
+    })
+}
+
+/// Future returned by [`spawn_deferred_operation()`].
+///
+/// Resolves to the result of the operation once all spawned joiners have returned. The
+/// operation must not be destroyed before the future has resolved.
+pub struct DeferredOperationFuture {
+    deferred_host_operations: DeferredHostOperations,
+    operation: vk::DeferredOperationKHR,
+    state: Arc<Mutex<JoinState>>,
+    spawn: Spawn,
+}
+
+impl Future for DeferredOperationFuture {
+    type Output = VkResult<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let mut state = this.state.lock().unwrap();
+        if state.running > 0 {
+            state.waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        if let Some(err) = state.error {
+            return Poll::Ready(Err(err));
+        }
+        // All joiners have returned, so the operation is complete unless it is also being joined
+        // from elsewhere. In that case keep a joiner on it instead of blocking the executor.
+        match unsafe {
+            this.deferred_host_operations
+                .get_deferred_operation_result(this.operation)
+        } {
+            Err(vk::Result::NOT_READY) => {
+                state.running += 1;
+                state.waker = Some(cx.waker().clone());
+                drop(state);
+                (this.spawn)(joiner(
+                    this.deferred_host_operations.clone(),
+                    this.operation,
+                    Arc::clone(&this.state),
+                ));
+                Poll::Pending
+            }
+            result => Poll::Ready(result),
+        }
+    }
+}
+
+/// Spawns up to `max_joiners` joiners for `operation`, bounded by
+/// [`DeferredHostOperations::get_deferred_operation_max_concurrency()`] but at least one, through
+/// `spawn`, which typically submits the closure to a thread pool. Every joiner blocks its thread
+/// until it has no more work. The future spawns another joiner when it is polled after all
+/// joiners have returned while the operation is still running.
+///
+/// ```no_run
+/// # use ash::{deferred::spawn_deferred_operation, extensions::khr::DeferredHostOperations, vk};
+/// # unsafe fn example(
+/// #     deferred_host_operations: &DeferredHostOperations,
+/// #     operation: vk::DeferredOperationKHR,
+/// # ) -> impl std::future::Future<Output = ash::prelude::VkResult<()>> {
+/// spawn_deferred_operation(deferred_host_operations, operation, 4, |joiner| {
+///     std::thread::spawn(joiner);
+/// })
+/// # }
+/// ```
+pub unsafe fn spawn_deferred_operation(
+    deferred_host_operations: &DeferredHostOperations,
+    operation: vk::DeferredOperationKHR,
+    max_joiners: usize,
+    spawn: impl FnMut(Box<dyn FnOnce() + Send + 'static>) + Send + 'static,
+) -> DeferredOperationFuture {
+    let joiners = joiner_count(deferred_host_operations, operation, max_joiners).max(1);
+    let state = Arc::new(Mutex::new(JoinState {
+        running: joiners,
+        ..Default::default()
+    }));
+    let mut spawn: Spawn = Box::new(spawn);
+    for _ in 0..joiners {
+        spawn(joiner(
+            deferred_host_operations.clone(),
+            operation,
+            Arc::clone(&state),
+        ));
+    }
+    DeferredOperationFuture {
+        deferred_host_operations: deferred_host_operations.clone(),
+        operation,
+        state,
+        spawn,
+    }
+}
@@ -0,0 +1,200 @@
+//! Presenting directly to a display through `VK_KHR_display`, without a windowing system.
+//!
+//! [`enumerate_display_targets()`] pairs every display connected to a physical device with its
+//! preferred mode and a plane that can scan it out. [`DisplayTarget::create_surface()`] then
+//! creates a surface that can be passed to
+//! [`SwapchainCreateDesc::surface`][crate::swapchain::SwapchainCreateDesc::surface] together with
+//! [`DisplayTarget::extent()`].
+//!
+//! On Linux the display may first have to be taken over from the DRM master with
+//! [`ext::AcquireDrmDisplay`], and handed back with [`ext::DirectModeDisplay`] when done.
+//! [`ext::DisplayControl`] adds display power control and vblank events.
+//!
+//! [`ext::AcquireDrmDisplay`]: crate::extensions::ext::AcquireDrmDisplay
+//! [`ext::DirectModeDisplay`]: crate::extensions::ext::DirectModeDisplay
+//! [`ext::DisplayControl`]: crate::extensions::ext::DisplayControl
+
+use crate::extensions::khr;
+use crate::prelude::*;
+use crate::vk;
+
+/// Picks the mode with the largest visible region, preferring higher refresh rates.
+fn choose_display_mode(
+    modes: &[vk::DisplayModePropertiesKHR],
+) -> Option<vk::DisplayModePropertiesKHR> {
+    modes.iter().copied().max_by_key(|mode| {
+        let region = mode.parameters.visible_region;
+        (
+            u64::from(region.width) * u64::from(region.height),
+            mode.parameters.refresh_rate,
+        )
+    })
+}
+
+/// Picks the first flag of `supported` in `preferred` order, falling back to the lowest
+/// supported bit.
+fn choose_flag(supported: u32, preferred: &[u32]) -> u32 {
+    preferred
+        .iter()
+        .copied()
+        .find(|&flag| supported & flag != 0)
+        .unwrap_or(supported & supported.wrapping_neg())
+}
+
+/// A display with a mode and a plane that can present to it.
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct DisplayTarget {
+    pub display: vk::DisplayKHR,
+    pub display_properties: vk::DisplayPropertiesKHR,
+    /// All modes of the display.
+    pub modes: Vec<vk::DisplayModePropertiesKHR>,
+    /// The selected mode, see [`DisplayTarget::set_mode()`].
+    pub mode: vk::DisplayModePropertiesKHR,
+    pub plane_index: u32,
+    pub plane_stack_index: u32,
+    /// Capabilities of the plane in the selected mode.
+    pub plane_capabilities: vk::DisplayPlaneCapabilitiesKHR,
+}
+
+impl DisplayTarget {
+    /// Size of the images presented in the selected mode.
+    pub fn extent(&self) -> vk::Extent2D {
+        self.mode.parameters.visible_region
+    }
+
+    /// Refresh rate of the selected mode in millihertz.
+    pub fn refresh_rate(&self) -> u32 {
+        self.mode.parameters.refresh_rate
+    }
+
+    /// Selects `mode`, one of [`DisplayTarget::modes`], and queries the plane capabilities for it.
+    pub unsafe fn set_mode(
+        &mut self,
+        display: &khr::Display,
+        physical_device: vk::PhysicalDevice,
+        mode: vk::DisplayModePropertiesKHR,
+    ) -> VkResult<()> {
+        self.plane_capabilities = display.get_display_plane_capabilities(
+            physical_device,
+            mode.display_mode,
+            self.plane_index,
+        )?;
+        self.mode = mode;
+        Ok(())
+    }
+
+    /// Creates a surface presenting to the plane in the selected mode, covering the whole
+    /// display. Uses opaque alpha and the identity transform where the plane and display
+    /// support them.
+    pub unsafe fn create_surface(
+        &self,
+        display: &khr::Display,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<vk::SurfaceKHR> {
+        let alpha_mode = vk::DisplayPlaneAlphaFlagsKHR::from_raw(choose_flag(
+            self.plane_capabilities.supported_alpha.as_raw(),
+            &[
+                vk::DisplayPlaneAlphaFlagsKHR::OPAQUE.as_raw(),
+                vk::DisplayPlaneAlphaFlagsKHR::GLOBAL.as_raw(),
+            ],
+        ));
+        let transform = vk::SurfaceTransformFlagsKHR::from_raw(choose_flag(
+            self.display_properties.supported_transforms.as_raw(),
+            &[vk::SurfaceTransformFlagsKHR::IDENTITY.as_raw()],
+        ));
+        let create_info = vk::DisplaySurfaceCreateInfoKHR::builder()
+            .display_mode(self.mode.display_mode)
+            .plane_index(self.plane_index)
+            .plane_stack_index(self.plane_stack_index)
+            .transform(transform)
+            .global_alpha(1.0)
+            .alpha_mode(alpha_mode)
+            .image_extent(self.extent());
+        display.create_display_plane_surface(&create_info, allocation_callbacks)
+    }
+}
+
+/// Returns a [`DisplayTarget`] for every display of `physical_device` that has at least one mode
+/// and a plane that supports it and is not in use by another display. The largest mode with the
+/// highest refresh rate is selected.
+pub unsafe fn enumerate_display_targets(
+    display: &khr::Display,
+    physical_device: vk::PhysicalDevice,
+) -> VkResult<Vec<DisplayTarget>> {
+    let planes = display.get_physical_device_display_plane_properties(physical_device)?;
+    let mut plane_displays = Vec::with_capacity(planes.len());
+    for plane_index in 0..planes.len() as u32 {
+        plane_displays
+            .push(display.get_display_plane_supported_displays(physical_device, plane_index)?);
+    }
+    let mut used_planes = vec![false; planes.len()];
+
+    let mut targets = Vec::new();
+    for display_properties in display.get_physical_device_display_properties(physical_device)? {
+        let handle = display_properties.display;
+        let modes = display.get_display_mode_properties(physical_device, handle)?;
+        let mode = match choose_display_mode(&modes) {
+            Some(mode) => mode,
+            None => continue,
+        };
+        let plane_index = (0..planes.len()).find(|&i| {
+            let current = planes[i].current_display;
+            !used_planes[i]
+                && plane_displays[i].contains(&handle)
+                && (current == vk::DisplayKHR::null() || current == handle)
+        });
+        let plane_index = match plane_index {
+            Some(index) => index,
+            None => continue,
+        };
+        used_planes[plane_index] = true;
+        let plane_capabilities = display.get_display_plane_capabilities(
+            physical_device,
+            mode.display_mode,
+            plane_index as u32,
+        )?;
+        targets.push(DisplayTarget {
+            display: handle,
+            display_properties,
+            modes,
+            mode,
+            plane_index: plane_index as u32,
+            plane_stack_index: planes[plane_index].current_stack_index,
+            plane_capabilities,
+        });
+    }
+    Ok(targets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mode(width: u32, height: u32, refresh_rate: u32) -> vk::DisplayModePropertiesKHR {
+        vk::DisplayModePropertiesKHR {
+            display_mode: vk::DisplayModeKHR::null(),
+            parameters: vk::DisplayModeParametersKHR {
+                visible_region: vk::Extent2D { width, height },
+                refresh_rate,
+            },
+        }
+    }
+
+    #[test]
+    fn preferred_display_mode() {
+        let modes = [
+            mode(1920, 1080, 60_000),
+            mode(3840, 2160, 30_000),
+            mode(3840, 2160, 60_000),
+            mode(1280, 720, 144_000),
+        ];
+        let chosen = choose_display_mode(&modes).unwrap();
+        assert_eq!(chosen.parameters.visible_region.width, 3840);
+        assert_eq!(chosen.parameters.refresh_rate, 60_000);
+        assert!(choose_display_mode(&[]).is_none());
+
+        assert_eq!(choose_flag(0b0110, &[0b0001, 0b0100]), 0b0100);
+        assert_eq!(choose_flag(0b0110, &[0b0001]), 0b0010);
+    }
+}
@@ -0,0 +1,64 @@
+use crate::extensions::device_or_instance_proc_addr;
+use crate::prelude::*;
+use crate::vk;
+use crate::{Device, Entry, Instance};
+use std::ffi::CStr;
+
+#[derive(Clone)]
+pub struct CalibratedTimestamps {
+    handle: vk::Device,
+    fp: vk::ExtCalibratedTimestampsFn,
+}
+
+impl CalibratedTimestamps {
+    pub fn new(entry: &Entry, instance: &Instance, device: &Device) -> Self {
+        let handle = device.handle();
+        let fp = vk::ExtCalibratedTimestampsFn::load(|name| unsafe {
+            device_or_instance_proc_addr(entry, instance, handle, name)
+        });
+        Self { handle, fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkGetPhysicalDeviceCalibrateableTimeDomainsEXT.html>"]
+    pub unsafe fn get_physical_device_calibrateable_time_domains(
+        &self,
+        physical_device: vk::PhysicalDevice,
+    ) -> VkResult<Vec<vk::TimeDomainEXT>> {
+        read_into_uninitialized_vector(|count, data| {
+            self.fp
+                .get_physical_device_calibrateable_time_domains_ext(physical_device, count, data)
+        })
+    }
+
+    /// Returns a tuple containing `(timestamps, max_deviation)`, with one timestamp for every
+    /// element of `info`.
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkGetCalibratedTimestampsEXT.html>"]
+    pub unsafe fn get_calibrated_timestamps(
+        &self,
+        info: &[vk::CalibratedTimestampInfoEXT],
+    ) -> VkResult<(Vec<u64>, u64)> {
+        let mut timestamps = vec![0u64; info.len()];
+        let mut max_deviation = 0u64;
+        self.fp
+            .get_calibrated_timestamps_ext(
+                self.handle,
+                info.len() as u32,
+                info.as_ptr(),
+                timestamps.as_mut_ptr(),
+                &mut max_deviation,
+            )
+            .result_with_success((timestamps, max_deviation))
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::ExtCalibratedTimestampsFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::ExtCalibratedTimestampsFn {
+        &self.fp
+    }
+
+    pub fn device(&self) -> vk::Device {
+        self.handle
+    }
+}
@@ -0,0 +1,39 @@
+use crate::vk;
+use crate::{Device, Instance};
+use std::ffi::CStr;
+use std::mem;
+
+#[derive(Clone)]
+pub struct ColorWriteEnable {
+    fp: vk::ExtColorWriteEnableFn,
+}
+
+impl ColorWriteEnable {
+    pub fn new(instance: &Instance, device: &Device) -> Self {
+        let fp = vk::ExtColorWriteEnableFn::load(|name| unsafe {
+            mem::transmute(instance.get_device_proc_addr(device.handle(), name.as_ptr()))
+        });
+        Self { fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdSetColorWriteEnableEXT.html>"]
+    pub unsafe fn cmd_set_color_write_enable(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        color_write_enables: &[vk::Bool32],
+    ) {
+        self.fp.cmd_set_color_write_enable_ext(
+            command_buffer,
+            color_write_enables.len() as u32,
+            color_write_enables.as_ptr(),
+        )
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::ExtColorWriteEnableFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::ExtColorWriteEnableFn {
+        &self.fp
+    }
+}
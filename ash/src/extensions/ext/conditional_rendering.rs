@@ -0,0 +1,41 @@
+use crate::vk;
+use crate::{Device, Instance};
+use std::ffi::CStr;
+use std::mem;
+
+#[derive(Clone)]
+pub struct ConditionalRendering {
+    fp: vk::ExtConditionalRenderingFn,
+}
+
+impl ConditionalRendering {
+    pub fn new(instance: &Instance, device: &Device) -> Self {
+        let fp = vk::ExtConditionalRenderingFn::load(|name| unsafe {
+            mem::transmute(instance.get_device_proc_addr(device.handle(), name.as_ptr()))
+        });
+        Self { fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdBeginConditionalRenderingEXT.html>"]
+    pub unsafe fn cmd_begin_conditional_rendering(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        conditional_rendering_begin: &vk::ConditionalRenderingBeginInfoEXT,
+    ) {
+        self.fp
+            .cmd_begin_conditional_rendering_ext(command_buffer, conditional_rendering_begin)
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdEndConditionalRenderingEXT.html>"]
+    pub unsafe fn cmd_end_conditional_rendering(&self, command_buffer: vk::CommandBuffer) {
+        self.fp.cmd_end_conditional_rendering_ext(command_buffer)
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::ExtConditionalRenderingFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::ExtConditionalRenderingFn {
+        &self.fp
+    }
+}
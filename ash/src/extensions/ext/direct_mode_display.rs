@@ -0,0 +1,44 @@
+use crate::prelude::*;
+use crate::vk;
+use crate::{Entry, Instance};
+use std::ffi::CStr;
+use std::mem;
+
+#[derive(Clone)]
+pub struct DirectModeDisplay {
+    handle: vk::Instance,
+    fp: vk::ExtDirectModeDisplayFn,
+}
+
+impl DirectModeDisplay {
+    pub fn new(entry: &Entry, instance: &Instance) -> Self {
+        let handle = instance.handle();
+        let fp = vk::ExtDirectModeDisplayFn::load(|name| unsafe {
+            mem::transmute(entry.get_instance_proc_addr(handle, name.as_ptr()))
+        });
+        Self { handle, fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkReleaseDisplayEXT.html>"]
+    pub unsafe fn release_display(
+        &self,
+        physical_device: vk::PhysicalDevice,
+        display: vk::DisplayKHR,
+    ) -> VkResult<()> {
+        self.fp
+            .release_display_ext(physical_device, display)
+            .result()
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::ExtDirectModeDisplayFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::ExtDirectModeDisplayFn {
+        &self.fp
+    }
+
+    pub fn instance(&self) -> vk::Instance {
+        self.handle
+    }
+}
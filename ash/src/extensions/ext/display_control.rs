@@ -0,0 +1,93 @@
+use crate::prelude::*;
+use crate::vk;
+use crate::RawPtr;
+use crate::{Device, Instance};
+use std::ffi::CStr;
+use std::mem;
+
+#[derive(Clone)]
+pub struct DisplayControl {
+    handle: vk::Device,
+    fp: vk::ExtDisplayControlFn,
+}
+
+impl DisplayControl {
+    pub fn new(instance: &Instance, device: &Device) -> Self {
+        let handle = device.handle();
+        let fp = vk::ExtDisplayControlFn::load(|name| unsafe {
+            mem::transmute(instance.get_device_proc_addr(handle, name.as_ptr()))
+        });
+        Self { handle, fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkDisplayPowerControlEXT.html>"]
+    pub unsafe fn display_power_control(
+        &self,
+        display: vk::DisplayKHR,
+        display_power_info: &vk::DisplayPowerInfoEXT,
+    ) -> VkResult<()> {
+        self.fp
+            .display_power_control_ext(self.handle, display, display_power_info)
+            .result()
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkRegisterDeviceEventEXT.html>"]
+    pub unsafe fn register_device_event(
+        &self,
+        device_event_info: &vk::DeviceEventInfoEXT,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<vk::Fence> {
+        let mut fence = mem::zeroed();
+        self.fp
+            .register_device_event_ext(
+                self.handle,
+                device_event_info,
+                allocation_callbacks.as_raw_ptr(),
+                &mut fence,
+            )
+            .result_with_success(fence)
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkRegisterDisplayEventEXT.html>"]
+    pub unsafe fn register_display_event(
+        &self,
+        display: vk::DisplayKHR,
+        display_event_info: &vk::DisplayEventInfoEXT,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<vk::Fence> {
+        let mut fence = mem::zeroed();
+        self.fp
+            .register_display_event_ext(
+                self.handle,
+                display,
+                display_event_info,
+                allocation_callbacks.as_raw_ptr(),
+                &mut fence,
+            )
+            .result_with_success(fence)
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkGetSwapchainCounterEXT.html>"]
+    pub unsafe fn get_swapchain_counter(
+        &self,
+        swapchain: vk::SwapchainKHR,
+        counter: vk::SurfaceCounterFlagsEXT,
+    ) -> VkResult<u64> {
+        let mut counter_value = 0;
+        self.fp
+            .get_swapchain_counter_ext(self.handle, swapchain, counter, &mut counter_value)
+            .result_with_success(counter_value)
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::ExtDisplayControlFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::ExtDisplayControlFn {
+        &self.fp
+    }
+
+    pub fn device(&self) -> vk::Device {
+        self.handle
+    }
+}
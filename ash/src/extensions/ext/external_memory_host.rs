@@ -0,0 +1,50 @@
+use crate::prelude::*;
+use crate::vk;
+use crate::{Device, Instance};
+use std::ffi::{c_void, CStr};
+use std::mem;
+
+#[derive(Clone)]
+pub struct ExternalMemoryHost {
+    handle: vk::Device,
+    fp: vk::ExtExternalMemoryHostFn,
+}
+
+impl ExternalMemoryHost {
+    pub fn new(instance: &Instance, device: &Device) -> Self {
+        let handle = device.handle();
+        let fp = vk::ExtExternalMemoryHostFn::load(|name| unsafe {
+            mem::transmute(instance.get_device_proc_addr(handle, name.as_ptr()))
+        });
+        Self { handle, fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkGetMemoryHostPointerPropertiesEXT.html>"]
+    pub unsafe fn get_memory_host_pointer_properties(
+        &self,
+        handle_type: vk::ExternalMemoryHandleTypeFlags,
+        host_pointer: *const c_void,
+    ) -> VkResult<vk::MemoryHostPointerPropertiesEXT> {
+        let mut memory_host_pointer_properties = Default::default();
+        self.fp
+            .get_memory_host_pointer_properties_ext(
+                self.handle,
+                handle_type,
+                host_pointer,
+                &mut memory_host_pointer_properties,
+            )
+            .result_with_success(memory_host_pointer_properties)
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::ExtExternalMemoryHostFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::ExtExternalMemoryHostFn {
+        &self.fp
+    }
+
+    pub fn device(&self) -> vk::Device {
+        self.handle
+    }
+}
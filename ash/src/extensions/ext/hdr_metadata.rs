@@ -0,0 +1,47 @@
+use crate::vk;
+use crate::{Device, Instance};
+use std::ffi::CStr;
+use std::mem;
+
+#[derive(Clone)]
+pub struct HdrMetadata {
+    handle: vk::Device,
+    fp: vk::ExtHdrMetadataFn,
+}
+
+impl HdrMetadata {
+    pub fn new(instance: &Instance, device: &Device) -> Self {
+        let handle = device.handle();
+        let fp = vk::ExtHdrMetadataFn::load(|name| unsafe {
+            mem::transmute(instance.get_device_proc_addr(handle, name.as_ptr()))
+        });
+        Self { handle, fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkSetHdrMetadataEXT.html>"]
+    pub unsafe fn set_hdr_metadata(
+        &self,
+        swapchains: &[vk::SwapchainKHR],
+        metadata: &[vk::HdrMetadataEXT],
+    ) {
+        assert_eq!(swapchains.len(), metadata.len());
+        self.fp.set_hdr_metadata_ext(
+            self.handle,
+            swapchains.len() as u32,
+            swapchains.as_ptr(),
+            metadata.as_ptr(),
+        )
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::ExtHdrMetadataFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::ExtHdrMetadataFn {
+        &self.fp
+    }
+
+    pub fn device(&self) -> vk::Device {
+        self.handle
+    }
+}
@@ -0,0 +1,37 @@
+use crate::vk;
+use crate::{Device, Instance};
+use std::ffi::CStr;
+use std::mem;
+
+#[derive(Clone)]
+pub struct LineRasterization {
+    fp: vk::ExtLineRasterizationFn,
+}
+
+impl LineRasterization {
+    pub fn new(instance: &Instance, device: &Device) -> Self {
+        let fp = vk::ExtLineRasterizationFn::load(|name| unsafe {
+            mem::transmute(instance.get_device_proc_addr(device.handle(), name.as_ptr()))
+        });
+        Self { fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdSetLineStippleEXT.html>"]
+    pub unsafe fn cmd_set_line_stipple(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        line_stipple_factor: u32,
+        line_stipple_pattern: u16,
+    ) {
+        self.fp
+            .cmd_set_line_stipple_ext(command_buffer, line_stipple_factor, line_stipple_pattern)
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::ExtLineRasterizationFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::ExtLineRasterizationFn {
+        &self.fp
+    }
+}
@@ -1,23 +1,59 @@
+pub use self::acquire_drm_display::AcquireDrmDisplay;
 pub use self::buffer_device_address::BufferDeviceAddress;
+pub use self::calibrated_timestamps::CalibratedTimestamps;
+pub use self::color_write_enable::ColorWriteEnable;
+pub use self::conditional_rendering::ConditionalRendering;
 #[allow(deprecated)]
 pub use self::debug_marker::DebugMarker;
 #[allow(deprecated)]
 pub use self::debug_report::DebugReport;
 pub use self::debug_utils::DebugUtils;
+pub use self::direct_mode_display::DirectModeDisplay;
+pub use self::display_control::DisplayControl;
 pub use self::extended_dynamic_state::ExtendedDynamicState;
+pub use self::extended_dynamic_state2::ExtendedDynamicState2;
+pub use self::external_memory_host::ExternalMemoryHost;
 pub use self::full_screen_exclusive::FullScreenExclusive;
+pub use self::hdr_metadata::HdrMetadata;
+pub use self::headless_surface::HeadlessSurface;
+pub use self::image_drm_format_modifier::ImageDrmFormatModifier;
+pub use self::line_rasterization::LineRasterization;
 pub use self::metal_surface::MetalSurface;
+pub use self::multi_draw::MultiDraw;
 pub use self::physical_device_drm::PhysicalDeviceDrm;
+pub use self::private_data::PrivateData;
+pub use self::sample_locations::SampleLocations;
 pub use self::tooling_info::ToolingInfo;
+pub use self::transform_feedback::TransformFeedback;
+pub use self::validation_cache::ValidationCache;
+pub use self::vertex_input_dynamic_state::VertexInputDynamicState;
 
+mod acquire_drm_display;
 mod buffer_device_address;
+mod calibrated_timestamps;
+mod color_write_enable;
+mod conditional_rendering;
 #[deprecated(note = "Please use the [DebugUtils](struct.DebugUtils.html) extension instead.")]
 mod debug_marker;
 #[deprecated(note = "Please use the [DebugUtils](struct.DebugUtils.html) extension instead.")]
 mod debug_report;
 mod debug_utils;
+mod direct_mode_display;
+mod display_control;
 mod extended_dynamic_state;
+mod extended_dynamic_state2;
+mod external_memory_host;
 mod full_screen_exclusive;
+mod hdr_metadata;
+mod headless_surface;
+mod image_drm_format_modifier;
+mod line_rasterization;
 mod metal_surface;
+mod multi_draw;
 mod physical_device_drm;
+mod private_data;
+mod sample_locations;
 mod tooling_info;
+mod transform_feedback;
+mod validation_cache;
+mod vertex_input_dynamic_state;
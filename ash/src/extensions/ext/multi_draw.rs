@@ -0,0 +1,70 @@
+use crate::vk;
+use crate::{Device, Instance};
+use std::ffi::CStr;
+use std::mem;
+use std::ptr;
+
+#[derive(Clone)]
+pub struct MultiDraw {
+    fp: vk::ExtMultiDrawFn,
+}
+
+impl MultiDraw {
+    pub fn new(instance: &Instance, device: &Device) -> Self {
+        let fp = vk::ExtMultiDrawFn::load(|name| unsafe {
+            mem::transmute(instance.get_device_proc_addr(device.handle(), name.as_ptr()))
+        });
+        Self { fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdDrawMultiEXT.html>"]
+    pub unsafe fn cmd_draw_multi(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        vertex_info: &[vk::MultiDrawInfoEXT],
+        instance_count: u32,
+        first_instance: u32,
+    ) {
+        self.fp.cmd_draw_multi_ext(
+            command_buffer,
+            vertex_info.len() as u32,
+            vertex_info.as_ptr(),
+            instance_count,
+            first_instance,
+            mem::size_of::<vk::MultiDrawInfoEXT>() as u32,
+        )
+    }
+
+    /// When `vertex_offset` is [`Some`], it overrides the [`vk::MultiDrawIndexedInfoEXT::vertex_offset`]
+    /// of every draw.
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdDrawMultiIndexedEXT.html>"]
+    pub unsafe fn cmd_draw_multi_indexed(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        index_info: &[vk::MultiDrawIndexedInfoEXT],
+        instance_count: u32,
+        first_instance: u32,
+        vertex_offset: Option<i32>,
+    ) {
+        let p_vertex_offset = vertex_offset
+            .as_ref()
+            .map_or(ptr::null(), |vertex_offset| vertex_offset);
+        self.fp.cmd_draw_multi_indexed_ext(
+            command_buffer,
+            index_info.len() as u32,
+            index_info.as_ptr(),
+            instance_count,
+            first_instance,
+            mem::size_of::<vk::MultiDrawIndexedInfoEXT>() as u32,
+            p_vertex_offset,
+        )
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::ExtMultiDrawFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::ExtMultiDrawFn {
+        &self.fp
+    }
+}
@@ -0,0 +1,50 @@
+use crate::extensions::device_or_instance_proc_addr;
+use crate::vk;
+use crate::{Device, Entry, Instance};
+use std::ffi::CStr;
+
+#[derive(Clone)]
+pub struct SampleLocations {
+    fp: vk::ExtSampleLocationsFn,
+}
+
+impl SampleLocations {
+    pub fn new(entry: &Entry, instance: &Instance, device: &Device) -> Self {
+        let fp = vk::ExtSampleLocationsFn::load(|name| unsafe {
+            device_or_instance_proc_addr(entry, instance, device.handle(), name)
+        });
+        Self { fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdSetSampleLocationsEXT.html>"]
+    pub unsafe fn cmd_set_sample_locations(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        sample_locations_info: &vk::SampleLocationsInfoEXT,
+    ) {
+        self.fp
+            .cmd_set_sample_locations_ext(command_buffer, sample_locations_info)
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkGetPhysicalDeviceMultisamplePropertiesEXT.html>"]
+    pub unsafe fn get_physical_device_multisample_properties(
+        &self,
+        physical_device: vk::PhysicalDevice,
+        samples: vk::SampleCountFlags,
+        multisample_properties: &mut vk::MultisamplePropertiesEXT,
+    ) {
+        self.fp.get_physical_device_multisample_properties_ext(
+            physical_device,
+            samples,
+            multisample_properties,
+        )
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::ExtSampleLocationsFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::ExtSampleLocationsFn {
+        &self.fp
+    }
+}
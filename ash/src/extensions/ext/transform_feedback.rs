@@ -0,0 +1,146 @@
+use crate::vk;
+use crate::{Device, Instance};
+use std::ffi::CStr;
+use std::mem;
+use std::ptr;
+
+#[derive(Clone)]
+pub struct TransformFeedback {
+    fp: vk::ExtTransformFeedbackFn,
+}
+
+impl TransformFeedback {
+    pub fn new(instance: &Instance, device: &Device) -> Self {
+        let fp = vk::ExtTransformFeedbackFn::load(|name| unsafe {
+            mem::transmute(instance.get_device_proc_addr(device.handle(), name.as_ptr()))
+        });
+        Self { fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdBindTransformFeedbackBuffersEXT.html>"]
+    pub unsafe fn cmd_bind_transform_feedback_buffers(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        first_binding: u32,
+        buffers: &[vk::Buffer],
+        offsets: &[vk::DeviceSize],
+        sizes: Option<&[vk::DeviceSize]>,
+    ) {
+        assert_eq!(offsets.len(), buffers.len());
+        let p_sizes = if let Some(sizes) = sizes {
+            assert_eq!(sizes.len(), buffers.len());
+            sizes.as_ptr()
+        } else {
+            ptr::null()
+        };
+        self.fp.cmd_bind_transform_feedback_buffers_ext(
+            command_buffer,
+            first_binding,
+            buffers.len() as u32,
+            buffers.as_ptr(),
+            offsets.as_ptr(),
+            p_sizes,
+        )
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdBeginTransformFeedbackEXT.html>"]
+    pub unsafe fn cmd_begin_transform_feedback(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        first_counter_buffer: u32,
+        counter_buffers: &[vk::Buffer],
+        counter_buffer_offsets: Option<&[vk::DeviceSize]>,
+    ) {
+        let p_counter_buffer_offsets = if let Some(offsets) = counter_buffer_offsets {
+            assert_eq!(offsets.len(), counter_buffers.len());
+            offsets.as_ptr()
+        } else {
+            ptr::null()
+        };
+        self.fp.cmd_begin_transform_feedback_ext(
+            command_buffer,
+            first_counter_buffer,
+            counter_buffers.len() as u32,
+            counter_buffers.as_ptr(),
+            p_counter_buffer_offsets,
+        )
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdEndTransformFeedbackEXT.html>"]
+    pub unsafe fn cmd_end_transform_feedback(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        first_counter_buffer: u32,
+        counter_buffers: &[vk::Buffer],
+        counter_buffer_offsets: Option<&[vk::DeviceSize]>,
+    ) {
+        let p_counter_buffer_offsets = if let Some(offsets) = counter_buffer_offsets {
+            assert_eq!(offsets.len(), counter_buffers.len());
+            offsets.as_ptr()
+        } else {
+            ptr::null()
+        };
+        self.fp.cmd_end_transform_feedback_ext(
+            command_buffer,
+            first_counter_buffer,
+            counter_buffers.len() as u32,
+            counter_buffers.as_ptr(),
+            p_counter_buffer_offsets,
+        )
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdBeginQueryIndexedEXT.html>"]
+    pub unsafe fn cmd_begin_query_indexed(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        query_pool: vk::QueryPool,
+        query: u32,
+        flags: vk::QueryControlFlags,
+        index: u32,
+    ) {
+        self.fp
+            .cmd_begin_query_indexed_ext(command_buffer, query_pool, query, flags, index)
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdEndQueryIndexedEXT.html>"]
+    pub unsafe fn cmd_end_query_indexed(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        query_pool: vk::QueryPool,
+        query: u32,
+        index: u32,
+    ) {
+        self.fp
+            .cmd_end_query_indexed_ext(command_buffer, query_pool, query, index)
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdDrawIndirectByteCountEXT.html>"]
+    pub unsafe fn cmd_draw_indirect_byte_count(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        instance_count: u32,
+        first_instance: u32,
+        counter_buffer: vk::Buffer,
+        counter_buffer_offset: vk::DeviceSize,
+        counter_offset: u32,
+        vertex_stride: u32,
+    ) {
+        self.fp.cmd_draw_indirect_byte_count_ext(
+            command_buffer,
+            instance_count,
+            first_instance,
+            counter_buffer,
+            counter_buffer_offset,
+            counter_offset,
+            vertex_stride,
+        )
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::ExtTransformFeedbackFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::ExtTransformFeedbackFn {
+        &self.fp
+    }
+}
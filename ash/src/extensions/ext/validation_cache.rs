@@ -0,0 +1,91 @@
+use crate::prelude::*;
+use crate::vk;
+use crate::RawPtr;
+use crate::{Device, Instance};
+use std::ffi::CStr;
+use std::mem;
+
+#[derive(Clone)]
+pub struct ValidationCache {
+    handle: vk::Device,
+    fp: vk::ExtValidationCacheFn,
+}
+
+impl ValidationCache {
+    pub fn new(instance: &Instance, device: &Device) -> Self {
+        let handle = device.handle();
+        let fp = vk::ExtValidationCacheFn::load(|name| unsafe {
+            mem::transmute(instance.get_device_proc_addr(handle, name.as_ptr()))
+        });
+        Self { handle, fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCreateValidationCacheEXT.html>"]
+    pub unsafe fn create_validation_cache(
+        &self,
+        create_info: &vk::ValidationCacheCreateInfoEXT,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<vk::ValidationCacheEXT> {
+        let mut validation_cache = mem::zeroed();
+        self.fp
+            .create_validation_cache_ext(
+                self.handle,
+                create_info,
+                allocation_callbacks.as_raw_ptr(),
+                &mut validation_cache,
+            )
+            .result_with_success(validation_cache)
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkDestroyValidationCacheEXT.html>"]
+    pub unsafe fn destroy_validation_cache(
+        &self,
+        validation_cache: vk::ValidationCacheEXT,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) {
+        self.fp.destroy_validation_cache_ext(
+            self.handle,
+            validation_cache,
+            allocation_callbacks.as_raw_ptr(),
+        );
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkMergeValidationCachesEXT.html>"]
+    pub unsafe fn merge_validation_caches(
+        &self,
+        dst_cache: vk::ValidationCacheEXT,
+        src_caches: &[vk::ValidationCacheEXT],
+    ) -> VkResult<()> {
+        self.fp
+            .merge_validation_caches_ext(
+                self.handle,
+                dst_cache,
+                src_caches.len() as u32,
+                src_caches.as_ptr(),
+            )
+            .result()
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkGetValidationCacheDataEXT.html>"]
+    pub unsafe fn get_validation_cache_data(
+        &self,
+        validation_cache: vk::ValidationCacheEXT,
+    ) -> VkResult<Vec<u8>> {
+        read_into_uninitialized_vector(|count, data| {
+            self.fp
+                .get_validation_cache_data_ext(self.handle, validation_cache, count, data as _)
+        })
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::ExtValidationCacheFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::ExtValidationCacheFn {
+        &self.fp
+    }
+
+    pub fn device(&self) -> vk::Device {
+        self.handle
+    }
+}
@@ -0,0 +1,42 @@
+use crate::vk;
+use crate::{Device, Instance};
+use std::ffi::CStr;
+use std::mem;
+
+#[derive(Clone)]
+pub struct VertexInputDynamicState {
+    fp: vk::ExtVertexInputDynamicStateFn,
+}
+
+impl VertexInputDynamicState {
+    pub fn new(instance: &Instance, device: &Device) -> Self {
+        let fp = vk::ExtVertexInputDynamicStateFn::load(|name| unsafe {
+            mem::transmute(instance.get_device_proc_addr(device.handle(), name.as_ptr()))
+        });
+        Self { fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdSetVertexInputEXT.html>"]
+    pub unsafe fn cmd_set_vertex_input(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        vertex_binding_descriptions: &[vk::VertexInputBindingDescription2EXT],
+        vertex_attribute_descriptions: &[vk::VertexInputAttributeDescription2EXT],
+    ) {
+        self.fp.cmd_set_vertex_input_ext(
+            command_buffer,
+            vertex_binding_descriptions.len() as u32,
+            vertex_binding_descriptions.as_ptr(),
+            vertex_attribute_descriptions.len() as u32,
+            vertex_attribute_descriptions.as_ptr(),
+        )
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::ExtVertexInputDynamicStateFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::ExtVertexInputDynamicStateFn {
+        &self.fp
+    }
+}
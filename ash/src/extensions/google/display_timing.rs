@@ -0,0 +1,55 @@
+use crate::prelude::*;
+use crate::vk;
+use crate::{Device, Instance};
+use std::ffi::CStr;
+use std::mem;
+
+#[derive(Clone)]
+pub struct DisplayTiming {
+    handle: vk::Device,
+    fp: vk::GoogleDisplayTimingFn,
+}
+
+impl DisplayTiming {
+    pub fn new(instance: &Instance, device: &Device) -> Self {
+        let handle = device.handle();
+        let fp = vk::GoogleDisplayTimingFn::load(|name| unsafe {
+            mem::transmute(instance.get_device_proc_addr(handle, name.as_ptr()))
+        });
+        Self { handle, fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkGetPastPresentationTimingGOOGLE.html>"]
+    pub unsafe fn get_past_presentation_timing(
+        &self,
+        swapchain: vk::SwapchainKHR,
+    ) -> VkResult<Vec<vk::PastPresentationTimingGOOGLE>> {
+        read_into_uninitialized_vector(|count, data| {
+            self.fp
+                .get_past_presentation_timing_google(self.handle, swapchain, count, data)
+        })
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkGetRefreshCycleDurationGOOGLE.html>"]
+    pub unsafe fn get_refresh_cycle_duration(
+        &self,
+        swapchain: vk::SwapchainKHR,
+    ) -> VkResult<vk::RefreshCycleDurationGOOGLE> {
+        let mut properties = mem::zeroed();
+        self.fp
+            .get_refresh_cycle_duration_google(self.handle, swapchain, &mut properties)
+            .result_with_success(properties)
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::GoogleDisplayTimingFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::GoogleDisplayTimingFn {
+        &self.fp
+    }
+
+    pub fn device(&self) -> vk::Device {
+        self.handle
+    }
+}
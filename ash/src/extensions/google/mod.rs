@@ -0,0 +1,3 @@
+pub use self::display_timing::DisplayTiming;
+
+mod display_timing;
@@ -0,0 +1,3 @@
+pub use self::performance_query::PerformanceQuery;
+
+mod performance_query;
@@ -0,0 +1,124 @@
+use crate::prelude::*;
+use crate::vk;
+use crate::{Device, Instance};
+use std::ffi::CStr;
+use std::mem;
+
+#[derive(Clone)]
+pub struct PerformanceQuery {
+    handle: vk::Device,
+    fp: vk::IntelPerformanceQueryFn,
+}
+
+impl PerformanceQuery {
+    pub fn new(instance: &Instance, device: &Device) -> Self {
+        let handle = device.handle();
+        let fp = vk::IntelPerformanceQueryFn::load(|name| unsafe {
+            mem::transmute(instance.get_device_proc_addr(handle, name.as_ptr()))
+        });
+        Self { handle, fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkInitializePerformanceApiINTEL.html>"]
+    pub unsafe fn initialize_performance_api(
+        &self,
+        initialize_info: &vk::InitializePerformanceApiInfoINTEL,
+    ) -> VkResult<()> {
+        self.fp
+            .initialize_performance_api_intel(self.handle, initialize_info)
+            .result()
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkUninitializePerformanceApiINTEL.html>"]
+    pub unsafe fn uninitialize_performance_api(&self) {
+        self.fp.uninitialize_performance_api_intel(self.handle)
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdSetPerformanceMarkerINTEL.html>"]
+    pub unsafe fn cmd_set_performance_marker(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        marker_info: &vk::PerformanceMarkerInfoINTEL,
+    ) -> VkResult<()> {
+        self.fp
+            .cmd_set_performance_marker_intel(command_buffer, marker_info)
+            .result()
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdSetPerformanceStreamMarkerINTEL.html>"]
+    pub unsafe fn cmd_set_performance_stream_marker(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        marker_info: &vk::PerformanceStreamMarkerInfoINTEL,
+    ) -> VkResult<()> {
+        self.fp
+            .cmd_set_performance_stream_marker_intel(command_buffer, marker_info)
+            .result()
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdSetPerformanceOverrideINTEL.html>"]
+    pub unsafe fn cmd_set_performance_override(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        override_info: &vk::PerformanceOverrideInfoINTEL,
+    ) -> VkResult<()> {
+        self.fp
+            .cmd_set_performance_override_intel(command_buffer, override_info)
+            .result()
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkAcquirePerformanceConfigurationINTEL.html>"]
+    pub unsafe fn acquire_performance_configuration(
+        &self,
+        acquire_info: &vk::PerformanceConfigurationAcquireInfoINTEL,
+    ) -> VkResult<vk::PerformanceConfigurationINTEL> {
+        let mut configuration = mem::zeroed();
+        self.fp
+            .acquire_performance_configuration_intel(self.handle, acquire_info, &mut configuration)
+            .result_with_success(configuration)
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkReleasePerformanceConfigurationINTEL.html>"]
+    pub unsafe fn release_performance_configuration(
+        &self,
+        configuration: vk::PerformanceConfigurationINTEL,
+    ) -> VkResult<()> {
+        self.fp
+            .release_performance_configuration_intel(self.handle, configuration)
+            .result()
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkQueueSetPerformanceConfigurationINTEL.html>"]
+    pub unsafe fn queue_set_performance_configuration(
+        &self,
+        queue: vk::Queue,
+        configuration: vk::PerformanceConfigurationINTEL,
+    ) -> VkResult<()> {
+        self.fp
+            .queue_set_performance_configuration_intel(queue, configuration)
+            .result()
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkGetPerformanceParameterINTEL.html>"]
+    pub unsafe fn get_performance_parameter(
+        &self,
+        parameter: vk::PerformanceParameterTypeINTEL,
+    ) -> VkResult<vk::PerformanceValueINTEL> {
+        let mut value = Default::default();
+        self.fp
+            .get_performance_parameter_intel(self.handle, parameter, &mut value)
+            .result_with_success(value)
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::IntelPerformanceQueryFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::IntelPerformanceQueryFn {
+        &self.fp
+    }
+
+    pub fn device(&self) -> vk::Device {
+        self.handle
+    }
+}
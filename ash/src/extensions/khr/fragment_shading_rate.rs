@@ -0,0 +1,49 @@
+use crate::extensions::device_or_instance_proc_addr;
+use crate::prelude::*;
+use crate::vk;
+use crate::{Device, Entry, Instance};
+use std::ffi::CStr;
+
+#[derive(Clone)]
+pub struct FragmentShadingRate {
+    fp: vk::KhrFragmentShadingRateFn,
+}
+
+impl FragmentShadingRate {
+    pub fn new(entry: &Entry, instance: &Instance, device: &Device) -> Self {
+        let fp = vk::KhrFragmentShadingRateFn::load(|name| unsafe {
+            device_or_instance_proc_addr(entry, instance, device.handle(), name)
+        });
+        Self { fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkGetPhysicalDeviceFragmentShadingRatesKHR.html>"]
+    pub unsafe fn get_physical_device_fragment_shading_rates(
+        &self,
+        physical_device: vk::PhysicalDevice,
+    ) -> VkResult<Vec<vk::PhysicalDeviceFragmentShadingRateKHR>> {
+        read_into_defaulted_vector(|count, data| {
+            self.fp
+                .get_physical_device_fragment_shading_rates_khr(physical_device, count, data)
+        })
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdSetFragmentShadingRateKHR.html>"]
+    pub unsafe fn cmd_set_fragment_shading_rate(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        fragment_size: &vk::Extent2D,
+        combiner_ops: &[vk::FragmentShadingRateCombinerOpKHR; 2],
+    ) {
+        self.fp
+            .cmd_set_fragment_shading_rate_khr(command_buffer, fragment_size, combiner_ops)
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::KhrFragmentShadingRateFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::KhrFragmentShadingRateFn {
+        &self.fp
+    }
+}
@@ -0,0 +1,147 @@
+use crate::prelude::*;
+use crate::vk;
+use crate::{Entry, Instance};
+use std::ffi::CStr;
+use std::mem;
+use std::ptr;
+
+#[derive(Clone)]
+pub struct GetDisplayProperties2 {
+    handle: vk::Instance,
+    fp: vk::KhrGetDisplayProperties2Fn,
+}
+
+impl GetDisplayProperties2 {
+    pub fn new(entry: &Entry, instance: &Instance) -> Self {
+        let handle = instance.handle();
+        let fp = vk::KhrGetDisplayProperties2Fn::load(|name| unsafe {
+            mem::transmute(entry.get_instance_proc_addr(handle, name.as_ptr()))
+        });
+        Self { handle, fp }
+    }
+
+    /// Retrieve the number of elements to pass to [`Self::get_physical_device_display_properties2()`]
+    pub unsafe fn get_physical_device_display_properties2_len(
+        &self,
+        physical_device: vk::PhysicalDevice,
+    ) -> VkResult<usize> {
+        let mut count = 0;
+        self.fp
+            .get_physical_device_display_properties2_khr(
+                physical_device,
+                &mut count,
+                ptr::null_mut(),
+            )
+            .result_with_success(count as usize)
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkGetPhysicalDeviceDisplayProperties2KHR.html>"]
+    ///
+    /// Call [`Self::get_physical_device_display_properties2_len()`] to query the number of elements to pass to `out`.
+    /// Be sure to [`Default::default()`]-initialize these elements and optionally set their `p_next` pointer.
+    pub unsafe fn get_physical_device_display_properties2(
+        &self,
+        physical_device: vk::PhysicalDevice,
+        out: &mut [vk::DisplayProperties2KHR],
+    ) -> VkResult<()> {
+        let mut count = out.len() as u32;
+        let err_code = self.fp.get_physical_device_display_properties2_khr(
+            physical_device,
+            &mut count,
+            out.as_mut_ptr(),
+        );
+        assert_eq!(count, out.len() as u32);
+        err_code.result()
+    }
+
+    /// Retrieve the number of elements to pass to [`Self::get_physical_device_display_plane_properties2()`]
+    pub unsafe fn get_physical_device_display_plane_properties2_len(
+        &self,
+        physical_device: vk::PhysicalDevice,
+    ) -> VkResult<usize> {
+        let mut count = 0;
+        self.fp
+            .get_physical_device_display_plane_properties2_khr(
+                physical_device,
+                &mut count,
+                ptr::null_mut(),
+            )
+            .result_with_success(count as usize)
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkGetPhysicalDeviceDisplayPlaneProperties2KHR.html>"]
+    ///
+    /// Call [`Self::get_physical_device_display_plane_properties2_len()`] to query the number of elements to pass to `out`.
+    /// Be sure to [`Default::default()`]-initialize these elements and optionally set their `p_next` pointer.
+    pub unsafe fn get_physical_device_display_plane_properties2(
+        &self,
+        physical_device: vk::PhysicalDevice,
+        out: &mut [vk::DisplayPlaneProperties2KHR],
+    ) -> VkResult<()> {
+        let mut count = out.len() as u32;
+        let err_code = self.fp.get_physical_device_display_plane_properties2_khr(
+            physical_device,
+            &mut count,
+            out.as_mut_ptr(),
+        );
+        assert_eq!(count, out.len() as u32);
+        err_code.result()
+    }
+
+    /// Retrieve the number of elements to pass to [`Self::get_display_mode_properties2()`]
+    pub unsafe fn get_display_mode_properties2_len(
+        &self,
+        physical_device: vk::PhysicalDevice,
+        display: vk::DisplayKHR,
+    ) -> VkResult<usize> {
+        let mut count = 0;
+        self.fp
+            .get_display_mode_properties2_khr(physical_device, display, &mut count, ptr::null_mut())
+            .result_with_success(count as usize)
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkGetDisplayModeProperties2KHR.html>"]
+    ///
+    /// Call [`Self::get_display_mode_properties2_len()`] to query the number of elements to pass to `out`.
+    /// Be sure to [`Default::default()`]-initialize these elements and optionally set their `p_next` pointer.
+    pub unsafe fn get_display_mode_properties2(
+        &self,
+        physical_device: vk::PhysicalDevice,
+        display: vk::DisplayKHR,
+        out: &mut [vk::DisplayModeProperties2KHR],
+    ) -> VkResult<()> {
+        let mut count = out.len() as u32;
+        let err_code = self.fp.get_display_mode_properties2_khr(
+            physical_device,
+            display,
+            &mut count,
+            out.as_mut_ptr(),
+        );
+        assert_eq!(count, out.len() as u32);
+        err_code.result()
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkGetDisplayPlaneCapabilities2KHR.html>"]
+    pub unsafe fn get_display_plane_capabilities2(
+        &self,
+        physical_device: vk::PhysicalDevice,
+        display_plane_info: &vk::DisplayPlaneInfo2KHR,
+        capabilities: &mut vk::DisplayPlaneCapabilities2KHR,
+    ) -> VkResult<()> {
+        self.fp
+            .get_display_plane_capabilities2_khr(physical_device, display_plane_info, capabilities)
+            .result()
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::KhrGetDisplayProperties2Fn::name()
+    }
+
+    pub fn fp(&self) -> &vk::KhrGetDisplayProperties2Fn {
+        &self.fp
+    }
+
+    pub fn instance(&self) -> vk::Instance {
+        self.handle
+    }
+}
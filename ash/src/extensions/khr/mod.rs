@@ -8,14 +8,20 @@ pub use self::display_swapchain::DisplaySwapchain;
 pub use self::draw_indirect_count::DrawIndirectCount;
 pub use self::dynamic_rendering::DynamicRendering;
 pub use self::external_fence_fd::ExternalFenceFd;
+pub use self::external_fence_win32::ExternalFenceWin32;
 pub use self::external_memory_fd::ExternalMemoryFd;
+pub use self::external_memory_win32::ExternalMemoryWin32;
 pub use self::external_semaphore_fd::ExternalSemaphoreFd;
+pub use self::external_semaphore_win32::ExternalSemaphoreWin32;
+pub use self::fragment_shading_rate::FragmentShadingRate;
+pub use self::get_display_properties2::GetDisplayProperties2;
 pub use self::get_memory_requirements2::GetMemoryRequirements2;
 pub use self::get_physical_device_properties2::GetPhysicalDeviceProperties2;
 pub use self::get_surface_capabilities2::GetSurfaceCapabilities2;
 pub use self::maintenance1::Maintenance1;
 pub use self::maintenance3::Maintenance3;
 pub use self::maintenance4::Maintenance4;
+pub use self::performance_query::{PerformanceCounterValue, PerformanceQuery};
 pub use self::pipeline_executable_properties::PipelineExecutableProperties;
 pub use self::present_wait::PresentWait;
 pub use self::push_descriptor::PushDescriptor;
@@ -24,6 +30,8 @@ pub use self::surface::Surface;
 pub use self::swapchain::Swapchain;
 pub use self::synchronization2::Synchronization2;
 pub use self::timeline_semaphore::TimelineSemaphore;
+pub use self::video_decode_queue::VideoDecodeQueue;
+pub use self::video_queue::VideoQueue;
 pub use self::wayland_surface::WaylandSurface;
 pub use self::win32_surface::Win32Surface;
 pub use self::xcb_surface::XcbSurface;
@@ -39,14 +47,20 @@ mod display_swapchain;
 mod draw_indirect_count;
 mod dynamic_rendering;
 mod external_fence_fd;
+mod external_fence_win32;
 mod external_memory_fd;
+mod external_memory_win32;
 mod external_semaphore_fd;
+mod external_semaphore_win32;
+mod fragment_shading_rate;
+mod get_display_properties2;
 mod get_memory_requirements2;
 mod get_physical_device_properties2;
 mod get_surface_capabilities2;
 mod maintenance1;
 mod maintenance3;
 mod maintenance4;
+mod performance_query;
 mod pipeline_executable_properties;
 mod present_wait;
 mod push_descriptor;
@@ -55,6 +69,8 @@ mod surface;
 mod swapchain;
 mod synchronization2;
 mod timeline_semaphore;
+mod video_decode_queue;
+mod video_queue;
 mod wayland_surface;
 mod win32_surface;
 mod xcb_surface;
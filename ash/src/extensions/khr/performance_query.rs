@@ -0,0 +1,204 @@
+use crate::extensions::device_or_instance_proc_addr;
+use crate::prelude::*;
+use crate::vk;
+use crate::{Device, Entry, Instance};
+use std::ffi::CStr;
+use std::ptr;
+
+#[derive(Clone)]
+pub struct PerformanceQuery {
+    handle: vk::Device,
+    fp: vk::KhrPerformanceQueryFn,
+}
+
+impl PerformanceQuery {
+    pub fn new(entry: &Entry, instance: &Instance, device: &Device) -> Self {
+        let handle = device.handle();
+        let fp = vk::KhrPerformanceQueryFn::load(|name| unsafe {
+            device_or_instance_proc_addr(entry, instance, handle, name)
+        });
+        Self { handle, fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkEnumeratePhysicalDeviceQueueFamilyPerformanceQueryCountersKHR.html>"]
+    ///
+    /// Returns the counters together with their descriptions, which share the same index.
+    pub unsafe fn enumerate_physical_device_queue_family_performance_query_counters(
+        &self,
+        physical_device: vk::PhysicalDevice,
+        queue_family_index: u32,
+    ) -> VkResult<(
+        Vec<vk::PerformanceCounterKHR>,
+        Vec<vk::PerformanceCounterDescriptionKHR>,
+    )> {
+        loop {
+            let mut count = 0;
+            self.fp
+                .enumerate_physical_device_queue_family_performance_query_counters_khr(
+                    physical_device,
+                    queue_family_index,
+                    &mut count,
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                )
+                .result()?;
+            let mut counters = vec![Default::default(); count as usize];
+            let mut descriptions = vec![Default::default(); count as usize];
+
+            let err_code = self
+                .fp
+                .enumerate_physical_device_queue_family_performance_query_counters_khr(
+                    physical_device,
+                    queue_family_index,
+                    &mut count,
+                    counters.as_mut_ptr(),
+                    descriptions.as_mut_ptr(),
+                );
+            if err_code != vk::Result::INCOMPLETE {
+                counters.truncate(count as usize);
+                descriptions.truncate(count as usize);
+                break err_code.result_with_success((counters, descriptions));
+            }
+        }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkGetPhysicalDeviceQueueFamilyPerformanceQueryPassesKHR.html>"]
+    pub unsafe fn get_physical_device_queue_family_performance_query_passes(
+        &self,
+        physical_device: vk::PhysicalDevice,
+        performance_query_create_info: &vk::QueryPoolPerformanceCreateInfoKHR,
+    ) -> u32 {
+        let mut num_passes = 0;
+        self.fp
+            .get_physical_device_queue_family_performance_query_passes_khr(
+                physical_device,
+                performance_query_create_info,
+                &mut num_passes,
+            );
+        num_passes
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkAcquireProfilingLockKHR.html>"]
+    pub unsafe fn acquire_profiling_lock(
+        &self,
+        info: &vk::AcquireProfilingLockInfoKHR,
+    ) -> VkResult<()> {
+        self.fp
+            .acquire_profiling_lock_khr(self.handle, info)
+            .result()
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkReleaseProfilingLockKHR.html>"]
+    pub unsafe fn release_profiling_lock(&self) {
+        self.fp.release_profiling_lock_khr(self.handle)
+    }
+
+    /// Decodes the results of a performance query, written by
+    /// [`Device::get_query_pool_results()`] with one [`vk::PerformanceCounterResultKHR`] for
+    /// every counter the query pool was created with. Values of counters with an unknown
+    /// [`vk::PerformanceCounterStorageKHR`] are [`None`].
+    pub fn decode_counter_results(
+        counters: &[vk::PerformanceCounterKHR],
+        results: &[vk::PerformanceCounterResultKHR],
+    ) -> Vec<Option<PerformanceCounterValue>> {
+        assert_eq!(counters.len(), results.len());
+        counters
+            .iter()
+            .zip(results)
+            .map(|(counter, result)| PerformanceCounterValue::new(counter.storage, result))
+            .collect()
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::KhrPerformanceQueryFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::KhrPerformanceQueryFn {
+        &self.fp
+    }
+
+    pub fn device(&self) -> vk::Device {
+        self.handle
+    }
+}
+
+/// A [`vk::PerformanceCounterResultKHR`] interpreted according to the
+/// [`vk::PerformanceCounterStorageKHR`] of its counter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PerformanceCounterValue {
+    Int32(i32),
+    Int64(i64),
+    Uint32(u32),
+    Uint64(u64),
+    Float32(f32),
+    Float64(f64),
+}
+
+impl PerformanceCounterValue {
+    /// Returns [`None`] for a `storage` type this version does not know about.
+    pub fn new(
+        storage: vk::PerformanceCounterStorageKHR,
+        result: &vk::PerformanceCounterResultKHR,
+    ) -> Option<Self> {
+        // Every bit pattern is a valid value for all members of the union.
+        unsafe {
+            Some(match storage {
+                vk::PerformanceCounterStorageKHR::INT32 => Self::Int32(result.int32),
+                vk::PerformanceCounterStorageKHR::INT64 => Self::Int64(result.int64),
+                vk::PerformanceCounterStorageKHR::UINT32 => Self::Uint32(result.uint32),
+                vk::PerformanceCounterStorageKHR::UINT64 => Self::Uint64(result.uint64),
+                vk::PerformanceCounterStorageKHR::FLOAT32 => Self::Float32(result.float32),
+                vk::PerformanceCounterStorageKHR::FLOAT64 => Self::Float64(result.float64),
+                _ => return None,
+            })
+        }
+    }
+
+    /// Converts the value to an `f64`, which may lose precision for large 64-bit integers.
+    pub fn as_f64(self) -> f64 {
+        match self {
+            Self::Int32(value) => value.into(),
+            Self::Int64(value) => value as f64,
+            Self::Uint32(value) => value.into(),
+            Self::Uint64(value) => value as f64,
+            Self::Float32(value) => value.into(),
+            Self::Float64(value) => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_counter_results() {
+        let counter = |storage| vk::PerformanceCounterKHR {
+            storage,
+            ..Default::default()
+        };
+        let counters = [
+            counter(vk::PerformanceCounterStorageKHR::INT32),
+            counter(vk::PerformanceCounterStorageKHR::UINT64),
+            counter(vk::PerformanceCounterStorageKHR::FLOAT64),
+            counter(vk::PerformanceCounterStorageKHR::from_raw(0x7fff)),
+        ];
+        let results = [
+            vk::PerformanceCounterResultKHR { int32: -3 },
+            vk::PerformanceCounterResultKHR { uint64: 1 << 40 },
+            vk::PerformanceCounterResultKHR { float64: 0.5 },
+            vk::PerformanceCounterResultKHR { uint32: 1 },
+        ];
+        let values = PerformanceQuery::decode_counter_results(&counters, &results);
+        assert_eq!(
+            values,
+            [
+                Some(PerformanceCounterValue::Int32(-3)),
+                Some(PerformanceCounterValue::Uint64(1 << 40)),
+                Some(PerformanceCounterValue::Float64(0.5)),
+                None,
+            ]
+        );
+        assert_eq!(values[0].unwrap().as_f64(), -3.0);
+    }
+}
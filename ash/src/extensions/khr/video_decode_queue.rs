@@ -0,0 +1,35 @@
+use crate::vk;
+use crate::{Device, Instance};
+use std::ffi::CStr;
+use std::mem;
+
+#[derive(Clone)]
+pub struct VideoDecodeQueue {
+    fp: vk::KhrVideoDecodeQueueFn,
+}
+
+impl VideoDecodeQueue {
+    pub fn new(instance: &Instance, device: &Device) -> Self {
+        let fp = vk::KhrVideoDecodeQueueFn::load(|name| unsafe {
+            mem::transmute(instance.get_device_proc_addr(device.handle(), name.as_ptr()))
+        });
+        Self { fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdDecodeVideoKHR.html>"]
+    pub unsafe fn cmd_decode_video(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        frame_info: &vk::VideoDecodeInfoKHR,
+    ) {
+        self.fp.cmd_decode_video_khr(command_buffer, frame_info)
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::KhrVideoDecodeQueueFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::KhrVideoDecodeQueueFn {
+        &self.fp
+    }
+}
@@ -0,0 +1,224 @@
+use crate::extensions::device_or_instance_proc_addr;
+use crate::prelude::*;
+use crate::vk;
+use crate::RawPtr;
+use crate::{Device, Entry, Instance};
+use std::ffi::CStr;
+use std::mem;
+use std::ptr;
+
+#[derive(Clone)]
+pub struct VideoQueue {
+    handle: vk::Device,
+    fp: vk::KhrVideoQueueFn,
+}
+
+impl VideoQueue {
+    pub fn new(entry: &Entry, instance: &Instance, device: &Device) -> Self {
+        let handle = device.handle();
+        let fp = vk::KhrVideoQueueFn::load(|name| unsafe {
+            device_or_instance_proc_addr(entry, instance, handle, name)
+        });
+        Self { handle, fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkGetPhysicalDeviceVideoCapabilitiesKHR.html>"]
+    ///
+    /// Chain the codec-specific capabilities structure into `capabilities` to retrieve it as well.
+    pub unsafe fn get_physical_device_video_capabilities(
+        &self,
+        physical_device: vk::PhysicalDevice,
+        video_profile: &vk::VideoProfileKHR,
+        capabilities: &mut vk::VideoCapabilitiesKHR,
+    ) -> VkResult<()> {
+        self.fp
+            .get_physical_device_video_capabilities_khr(
+                physical_device,
+                video_profile,
+                capabilities,
+            )
+            .result()
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkGetPhysicalDeviceVideoFormatPropertiesKHR.html>"]
+    pub unsafe fn get_physical_device_video_format_properties(
+        &self,
+        physical_device: vk::PhysicalDevice,
+        video_format_info: &vk::PhysicalDeviceVideoFormatInfoKHR,
+    ) -> VkResult<Vec<vk::VideoFormatPropertiesKHR>> {
+        read_into_defaulted_vector(|count, data| {
+            self.fp.get_physical_device_video_format_properties_khr(
+                physical_device,
+                video_format_info,
+                count,
+                data,
+            )
+        })
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCreateVideoSessionKHR.html>"]
+    pub unsafe fn create_video_session(
+        &self,
+        create_info: &vk::VideoSessionCreateInfoKHR,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<vk::VideoSessionKHR> {
+        let mut video_session = mem::zeroed();
+        self.fp
+            .create_video_session_khr(
+                self.handle,
+                create_info,
+                allocation_callbacks.as_raw_ptr(),
+                &mut video_session,
+            )
+            .result_with_success(video_session)
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkDestroyVideoSessionKHR.html>"]
+    pub unsafe fn destroy_video_session(
+        &self,
+        video_session: vk::VideoSessionKHR,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) {
+        self.fp.destroy_video_session_khr(
+            self.handle,
+            video_session,
+            allocation_callbacks.as_raw_ptr(),
+        )
+    }
+
+    /// Retrieve the number of elements to pass to [`Self::get_video_session_memory_requirements()`]
+    pub unsafe fn get_video_session_memory_requirements_len(
+        &self,
+        video_session: vk::VideoSessionKHR,
+    ) -> VkResult<usize> {
+        let mut count = 0;
+        self.fp
+            .get_video_session_memory_requirements_khr(
+                self.handle,
+                video_session,
+                &mut count,
+                ptr::null_mut(),
+            )
+            .result_with_success(count as usize)
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkGetVideoSessionMemoryRequirementsKHR.html>"]
+    ///
+    /// Call [`Self::get_video_session_memory_requirements_len()`] to query the number of elements to pass to `out`.
+    /// Every element must point `p_memory_requirements` to a [`Default::default()`]-initialized
+    /// [`vk::MemoryRequirements2`].
+    pub unsafe fn get_video_session_memory_requirements(
+        &self,
+        video_session: vk::VideoSessionKHR,
+        out: &mut [vk::VideoGetMemoryPropertiesKHR],
+    ) -> VkResult<()> {
+        let mut count = out.len() as u32;
+        self.fp
+            .get_video_session_memory_requirements_khr(
+                self.handle,
+                video_session,
+                &mut count,
+                out.as_mut_ptr(),
+            )
+            .result()
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkBindVideoSessionMemoryKHR.html>"]
+    pub unsafe fn bind_video_session_memory(
+        &self,
+        video_session: vk::VideoSessionKHR,
+        bind_memories: &[vk::VideoBindMemoryKHR],
+    ) -> VkResult<()> {
+        self.fp
+            .bind_video_session_memory_khr(
+                self.handle,
+                video_session,
+                bind_memories.len() as u32,
+                bind_memories.as_ptr(),
+            )
+            .result()
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCreateVideoSessionParametersKHR.html>"]
+    pub unsafe fn create_video_session_parameters(
+        &self,
+        create_info: &vk::VideoSessionParametersCreateInfoKHR,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<vk::VideoSessionParametersKHR> {
+        let mut video_session_parameters = mem::zeroed();
+        self.fp
+            .create_video_session_parameters_khr(
+                self.handle,
+                create_info,
+                allocation_callbacks.as_raw_ptr(),
+                &mut video_session_parameters,
+            )
+            .result_with_success(video_session_parameters)
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkUpdateVideoSessionParametersKHR.html>"]
+    pub unsafe fn update_video_session_parameters(
+        &self,
+        video_session_parameters: vk::VideoSessionParametersKHR,
+        update_info: &vk::VideoSessionParametersUpdateInfoKHR,
+    ) -> VkResult<()> {
+        self.fp
+            .update_video_session_parameters_khr(self.handle, video_session_parameters, update_info)
+            .result()
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkDestroyVideoSessionParametersKHR.html>"]
+    pub unsafe fn destroy_video_session_parameters(
+        &self,
+        video_session_parameters: vk::VideoSessionParametersKHR,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) {
+        self.fp.destroy_video_session_parameters_khr(
+            self.handle,
+            video_session_parameters,
+            allocation_callbacks.as_raw_ptr(),
+        )
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdBeginVideoCodingKHR.html>"]
+    pub unsafe fn cmd_begin_video_coding(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        begin_info: &vk::VideoBeginCodingInfoKHR,
+    ) {
+        self.fp
+            .cmd_begin_video_coding_khr(command_buffer, begin_info)
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdEndVideoCodingKHR.html>"]
+    pub unsafe fn cmd_end_video_coding(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        end_coding_info: &vk::VideoEndCodingInfoKHR,
+    ) {
+        self.fp
+            .cmd_end_video_coding_khr(command_buffer, end_coding_info)
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdControlVideoCodingKHR.html>"]
+    pub unsafe fn cmd_control_video_coding(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        coding_control_info: &vk::VideoCodingControlInfoKHR,
+    ) {
+        self.fp
+            .cmd_control_video_coding_khr(command_buffer, coding_control_info)
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::KhrVideoQueueFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::KhrVideoQueueFn {
+        &self.fp
+    }
+
+    pub fn device(&self) -> vk::Device {
+        self.handle
+    }
+}
@@ -1,6 +1,29 @@
+use crate::vk;
+use crate::{Entry, Instance};
+use std::ffi::{c_void, CStr};
+use std::mem;
+
 pub mod experimental;
 pub mod ext;
+pub mod google;
+pub mod intel;
 pub mod khr;
 pub mod mvk;
 pub mod nn;
 pub mod nv;
+
+/// Loads `name` through `vkGetDeviceProcAddr`, falling back to `vkGetInstanceProcAddr` for the
+/// physical device commands of device extensions, which are only exposed at instance level. This
+/// relies on `vkGetDeviceProcAddr` returning NULL for instance-level commands.
+pub(crate) unsafe fn device_or_instance_proc_addr(
+    entry: &Entry,
+    instance: &Instance,
+    device: vk::Device,
+    name: &CStr,
+) -> *const c_void {
+    mem::transmute(
+        instance
+            .get_device_proc_addr(device, name.as_ptr())
+            .or_else(|| entry.get_instance_proc_addr(instance.handle(), name.as_ptr())),
+    )
+}
@@ -0,0 +1,384 @@
+use crate::prelude::*;
+use crate::vk;
+use crate::RawPtr;
+use crate::{Device, Instance};
+use std::ffi::CStr;
+use std::mem;
+
+#[derive(Clone)]
+pub struct DeviceGeneratedCommands {
+    handle: vk::Device,
+    fp: vk::NvDeviceGeneratedCommandsFn,
+}
+
+impl DeviceGeneratedCommands {
+    pub fn new(instance: &Instance, device: &Device) -> Self {
+        let handle = device.handle();
+        let fp = vk::NvDeviceGeneratedCommandsFn::load(|name| unsafe {
+            mem::transmute(instance.get_device_proc_addr(handle, name.as_ptr()))
+        });
+        Self { handle, fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkGetGeneratedCommandsMemoryRequirementsNV.html>"]
+    pub unsafe fn get_generated_commands_memory_requirements(
+        &self,
+        info: &vk::GeneratedCommandsMemoryRequirementsInfoNV,
+        memory_requirements: &mut vk::MemoryRequirements2,
+    ) {
+        self.fp.get_generated_commands_memory_requirements_nv(
+            self.handle,
+            info,
+            memory_requirements,
+        )
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdPreprocessGeneratedCommandsNV.html>"]
+    pub unsafe fn cmd_preprocess_generated_commands(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        generated_commands_info: &vk::GeneratedCommandsInfoNV,
+    ) {
+        self.fp
+            .cmd_preprocess_generated_commands_nv(command_buffer, generated_commands_info)
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdExecuteGeneratedCommandsNV.html>"]
+    pub unsafe fn cmd_execute_generated_commands(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        is_preprocessed: bool,
+        generated_commands_info: &vk::GeneratedCommandsInfoNV,
+    ) {
+        self.fp.cmd_execute_generated_commands_nv(
+            command_buffer,
+            is_preprocessed.into(),
+            generated_commands_info,
+        )
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdBindPipelineShaderGroupNV.html>"]
+    pub unsafe fn cmd_bind_pipeline_shader_group(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        pipeline_bind_point: vk::PipelineBindPoint,
+        pipeline: vk::Pipeline,
+        group_index: u32,
+    ) {
+        self.fp.cmd_bind_pipeline_shader_group_nv(
+            command_buffer,
+            pipeline_bind_point,
+            pipeline,
+            group_index,
+        )
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCreateIndirectCommandsLayoutNV.html>"]
+    pub unsafe fn create_indirect_commands_layout(
+        &self,
+        create_info: &vk::IndirectCommandsLayoutCreateInfoNV,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<vk::IndirectCommandsLayoutNV> {
+        let mut indirect_commands_layout = mem::zeroed();
+        self.fp
+            .create_indirect_commands_layout_nv(
+                self.handle,
+                create_info,
+                allocation_callbacks.as_raw_ptr(),
+                &mut indirect_commands_layout,
+            )
+            .result_with_success(indirect_commands_layout)
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkDestroyIndirectCommandsLayoutNV.html>"]
+    pub unsafe fn destroy_indirect_commands_layout(
+        &self,
+        indirect_commands_layout: vk::IndirectCommandsLayoutNV,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) {
+        self.fp.destroy_indirect_commands_layout_nv(
+            self.handle,
+            indirect_commands_layout,
+            allocation_callbacks.as_raw_ptr(),
+        )
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::NvDeviceGeneratedCommandsFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::NvDeviceGeneratedCommandsFn {
+        &self.fp
+    }
+
+    pub fn device(&self) -> vk::Device {
+        self.handle
+    }
+}
+
+/// A command of an indirect commands layout, laid out in its stream by
+/// [`IndirectCommandsLayoutBuilder`].
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum IndirectCommandsToken<'a> {
+    /// Reads a [`vk::BindShaderGroupIndirectCommandNV`].
+    ShaderGroup,
+    /// Reads a [`vk::SetStateFlagsIndirectCommandNV`].
+    StateFlags {
+        indirect_state_flags: vk::IndirectStateFlagsNV,
+    },
+    /// Reads a [`vk::BindIndexBufferIndirectCommandNV`], whose `index_type` is optionally
+    /// remapped from `index_type_values` to the [`vk::IndexType`] at the same position in
+    /// `index_types`.
+    IndexBuffer {
+        index_types: &'a [vk::IndexType],
+        index_type_values: &'a [u32],
+    },
+    /// Reads a [`vk::BindVertexBufferIndirectCommandNV`].
+    VertexBuffer {
+        vertex_binding_unit: u32,
+        vertex_dynamic_stride: bool,
+    },
+    /// Reads `size` bytes of push constants.
+    PushConstant {
+        pipeline_layout: vk::PipelineLayout,
+        shader_stage_flags: vk::ShaderStageFlags,
+        offset: u32,
+        size: u32,
+    },
+    /// Reads a [`vk::DrawIndexedIndirectCommand`].
+    DrawIndexed,
+    /// Reads a [`vk::DrawIndirectCommand`].
+    Draw,
+    /// Reads a [`vk::DrawMeshTasksIndirectCommandNV`].
+    DrawTasks,
+}
+
+impl IndirectCommandsToken<'_> {
+    fn token_type(&self) -> vk::IndirectCommandsTokenTypeNV {
+        match self {
+            Self::ShaderGroup => vk::IndirectCommandsTokenTypeNV::SHADER_GROUP,
+            Self::StateFlags { .. } => vk::IndirectCommandsTokenTypeNV::STATE_FLAGS,
+            Self::IndexBuffer { .. } => vk::IndirectCommandsTokenTypeNV::INDEX_BUFFER,
+            Self::VertexBuffer { .. } => vk::IndirectCommandsTokenTypeNV::VERTEX_BUFFER,
+            Self::PushConstant { .. } => vk::IndirectCommandsTokenTypeNV::PUSH_CONSTANT,
+            Self::DrawIndexed => vk::IndirectCommandsTokenTypeNV::DRAW_INDEXED,
+            Self::Draw => vk::IndirectCommandsTokenTypeNV::DRAW,
+            Self::DrawTasks => vk::IndirectCommandsTokenTypeNV::DRAW_TASKS,
+        }
+    }
+
+    /// Size and alignment of the data read for this token.
+    fn layout(&self) -> (u32, u32) {
+        fn layout_of<T>() -> (u32, u32) {
+            (mem::size_of::<T>() as u32, mem::align_of::<T>() as u32)
+        }
+        match self {
+            Self::ShaderGroup => layout_of::<vk::BindShaderGroupIndirectCommandNV>(),
+            Self::StateFlags { .. } => layout_of::<vk::SetStateFlagsIndirectCommandNV>(),
+            Self::IndexBuffer { .. } => layout_of::<vk::BindIndexBufferIndirectCommandNV>(),
+            Self::VertexBuffer { .. } => layout_of::<vk::BindVertexBufferIndirectCommandNV>(),
+            Self::PushConstant { size, .. } => (*size, 4),
+            Self::DrawIndexed => layout_of::<vk::DrawIndexedIndirectCommand>(),
+            Self::Draw => layout_of::<vk::DrawIndirectCommand>(),
+            Self::DrawTasks => layout_of::<vk::DrawMeshTasksIndirectCommandNV>(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct StreamLayout {
+    size: u32,
+    alignment: u32,
+}
+
+/// Composes the tokens and stream strides of a [`vk::IndirectCommandsLayoutCreateInfoNV`].
+///
+/// Every token is placed after the previous tokens of its stream, aligned to the alignment of
+/// the data it reads, and every stream stride is rounded up to the largest alignment in the
+/// stream.
+///
+/// ```
+/// # use ash::{extensions::nv::{IndirectCommandsLayoutBuilder, IndirectCommandsToken}, vk};
+/// let layout = IndirectCommandsLayoutBuilder::new(vk::PipelineBindPoint::GRAPHICS)
+///     .token(0, IndirectCommandsToken::ShaderGroup)
+///     .token(0, IndirectCommandsToken::DrawIndexed);
+/// assert_eq!(layout.token_offsets().collect::<Vec<_>>(), [0, 4]);
+/// assert_eq!(layout.stream_strides(), [24]);
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct IndirectCommandsLayoutBuilder<'a> {
+    flags: vk::IndirectCommandsLayoutUsageFlagsNV,
+    pipeline_bind_point: vk::PipelineBindPoint,
+    min_token_offset_alignment: u32,
+    tokens: Vec<IndirectCommandsToken<'a>>,
+    token_streams: Vec<u32>,
+    token_offsets: Vec<u32>,
+    streams: Vec<StreamLayout>,
+    stream_strides: Vec<u32>,
+}
+
+impl<'a> IndirectCommandsLayoutBuilder<'a> {
+    pub fn new(pipeline_bind_point: vk::PipelineBindPoint) -> Self {
+        Self {
+            flags: vk::IndirectCommandsLayoutUsageFlagsNV::empty(),
+            pipeline_bind_point,
+            min_token_offset_alignment: 1,
+            tokens: Vec::new(),
+            token_streams: Vec::new(),
+            token_offsets: Vec::new(),
+            streams: Vec::new(),
+            stream_strides: Vec::new(),
+        }
+    }
+
+    pub fn flags(mut self, flags: vk::IndirectCommandsLayoutUsageFlagsNV) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Aligns every token to at least `alignment` bytes, in addition to the alignment of the
+    /// data it reads. Only affects tokens added afterwards.
+    pub fn min_token_offset_alignment(mut self, alignment: u32) -> Self {
+        assert!(alignment.is_power_of_two());
+        self.min_token_offset_alignment = alignment;
+        self
+    }
+
+    /// Appends `token` to the end of `stream`.
+    pub fn token(mut self, stream: u32, token: IndirectCommandsToken<'a>) -> Self {
+        if let IndirectCommandsToken::IndexBuffer {
+            index_types,
+            index_type_values,
+        } = token
+        {
+            assert_eq!(index_types.len(), index_type_values.len());
+        }
+        let stream_index = stream as usize;
+        if self.streams.len() <= stream_index {
+            self.streams
+                .resize(stream_index + 1, StreamLayout::default());
+            self.stream_strides.resize(stream_index + 1, 0);
+        }
+        let (size, alignment) = token.layout();
+        let alignment = alignment.max(self.min_token_offset_alignment);
+        let layout = &mut self.streams[stream_index];
+        let offset = layout.size + (alignment - layout.size % alignment) % alignment;
+        layout.size = offset + size;
+        layout.alignment = layout.alignment.max(alignment);
+        self.stream_strides[stream_index] =
+            layout.size + (layout.alignment - layout.size % layout.alignment) % layout.alignment;
+
+        self.tokens.push(token);
+        self.token_streams.push(stream);
+        self.token_offsets.push(offset);
+        self
+    }
+
+    /// Offset of every token within its stream, in the order the tokens were added.
+    pub fn token_offsets(&self) -> impl Iterator<Item = u32> + '_ {
+        self.token_offsets.iter().copied()
+    }
+
+    /// Stride of every stream, indexed by stream.
+    pub fn stream_strides(&self) -> &[u32] {
+        &self.stream_strides
+    }
+
+    /// Returns the layout tokens, which borrow the index type remapping slices of the
+    /// [`IndirectCommandsToken::IndexBuffer`] tokens.
+    pub fn layout_tokens(&self) -> Vec<vk::IndirectCommandsLayoutTokenNV> {
+        self.tokens
+            .iter()
+            .zip(&self.token_streams)
+            .zip(&self.token_offsets)
+            .map(|((token, &stream), &offset)| {
+                let mut layout_token = vk::IndirectCommandsLayoutTokenNV::builder()
+                    .token_type(token.token_type())
+                    .stream(stream)
+                    .offset(offset);
+                match *token {
+                    IndirectCommandsToken::StateFlags {
+                        indirect_state_flags,
+                    } => layout_token = layout_token.indirect_state_flags(indirect_state_flags),
+                    IndirectCommandsToken::IndexBuffer {
+                        index_types,
+                        index_type_values,
+                    } => {
+                        layout_token = layout_token
+                            .index_types(index_types)
+                            .index_type_values(index_type_values)
+                    }
+                    IndirectCommandsToken::VertexBuffer {
+                        vertex_binding_unit,
+                        vertex_dynamic_stride,
+                    } => {
+                        layout_token = layout_token
+                            .vertex_binding_unit(vertex_binding_unit)
+                            .vertex_dynamic_stride(vertex_dynamic_stride)
+                    }
+                    IndirectCommandsToken::PushConstant {
+                        pipeline_layout,
+                        shader_stage_flags,
+                        offset,
+                        size,
+                    } => {
+                        layout_token = layout_token
+                            .pushconstant_pipeline_layout(pipeline_layout)
+                            .pushconstant_shader_stage_flags(shader_stage_flags)
+                            .pushconstant_offset(offset)
+                            .pushconstant_size(size)
+                    }
+                    _ => {}
+                }
+                layout_token.build()
+            })
+            .collect()
+    }
+
+    /// Creates the indirect commands layout.
+    pub unsafe fn create(
+        &self,
+        device_generated_commands: &DeviceGeneratedCommands,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<vk::IndirectCommandsLayoutNV> {
+        let tokens = self.layout_tokens();
+        let create_info = vk::IndirectCommandsLayoutCreateInfoNV::builder()
+            .flags(self.flags)
+            .pipeline_bind_point(self.pipeline_bind_point)
+            .tokens(&tokens)
+            .stream_strides(&self.stream_strides);
+        device_generated_commands
+            .create_indirect_commands_layout(&create_info, allocation_callbacks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_offsets_and_strides() {
+        let index_types = [vk::IndexType::UINT16, vk::IndexType::UINT32];
+        let index_type_values = [0, 1];
+        let layout = IndirectCommandsLayoutBuilder::new(vk::PipelineBindPoint::GRAPHICS)
+            .token(0, IndirectCommandsToken::ShaderGroup)
+            .token(
+                0,
+                IndirectCommandsToken::IndexBuffer {
+                    index_types: &index_types,
+                    index_type_values: &index_type_values,
+                },
+            )
+            .token(0, IndirectCommandsToken::DrawIndexed)
+            .token(1, IndirectCommandsToken::Draw);
+        assert_eq!(layout.token_offsets().collect::<Vec<_>>(), [0, 8, 24, 0]);
+        assert_eq!(layout.stream_strides(), [48, 16]);
+
+        let tokens = layout.layout_tokens();
+        assert!(tokens[1].token_type == vk::IndirectCommandsTokenTypeNV::INDEX_BUFFER);
+        assert_eq!(tokens[1].index_type_count, 2);
+        assert_eq!(tokens[3].stream, 1);
+    }
+}
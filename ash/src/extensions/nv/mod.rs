@@ -1,7 +1,13 @@
 pub use self::device_diagnostic_checkpoints::DeviceDiagnosticCheckpoints;
+pub use self::device_generated_commands::{
+    DeviceGeneratedCommands, IndirectCommandsLayoutBuilder, IndirectCommandsToken,
+};
 pub use self::mesh_shader::MeshShader;
 pub use self::ray_tracing::RayTracing;
+pub use self::shading_rate_image::ShadingRateImage;
 
 mod device_diagnostic_checkpoints;
+mod device_generated_commands;
 mod mesh_shader;
 mod ray_tracing;
+mod shading_rate_image;
@@ -0,0 +1,67 @@
+use crate::vk;
+use crate::{Device, Instance};
+use std::ffi::CStr;
+use std::mem;
+
+#[derive(Clone)]
+pub struct ShadingRateImage {
+    fp: vk::NvShadingRateImageFn,
+}
+
+impl ShadingRateImage {
+    pub fn new(instance: &Instance, device: &Device) -> Self {
+        let fp = vk::NvShadingRateImageFn::load(|name| unsafe {
+            mem::transmute(instance.get_device_proc_addr(device.handle(), name.as_ptr()))
+        });
+        Self { fp }
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdBindShadingRateImageNV.html>"]
+    pub unsafe fn cmd_bind_shading_rate_image(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        image_view: vk::ImageView,
+        image_layout: vk::ImageLayout,
+    ) {
+        self.fp
+            .cmd_bind_shading_rate_image_nv(command_buffer, image_view, image_layout)
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdSetViewportShadingRatePaletteNV.html>"]
+    pub unsafe fn cmd_set_viewport_shading_rate_palette(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        first_viewport: u32,
+        shading_rate_palettes: &[vk::ShadingRatePaletteNV],
+    ) {
+        self.fp.cmd_set_viewport_shading_rate_palette_nv(
+            command_buffer,
+            first_viewport,
+            shading_rate_palettes.len() as u32,
+            shading_rate_palettes.as_ptr(),
+        )
+    }
+
+    #[doc = "<https://www.khronos.org/registry/vulkan/specs/1.2-extensions/man/html/vkCmdSetCoarseSampleOrderNV.html>"]
+    pub unsafe fn cmd_set_coarse_sample_order(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        sample_order_type: vk::CoarseSampleOrderTypeNV,
+        custom_sample_orders: &[vk::CoarseSampleOrderCustomNV],
+    ) {
+        self.fp.cmd_set_coarse_sample_order_nv(
+            command_buffer,
+            sample_order_type,
+            custom_sample_orders.len() as u32,
+            custom_sample_orders.as_ptr(),
+        )
+    }
+
+    pub fn name() -> &'static CStr {
+        vk::NvShadingRateImageFn::name()
+    }
+
+    pub fn fp(&self) -> &vk::NvShadingRateImageFn {
+        &self.fp
+    }
+}
@@ -39,11 +39,23 @@ pub use crate::entry::Entry;
 pub use crate::entry::LoadingError;
 pub use crate::instance::Instance;
 
+pub mod alloc;
+pub mod deferred;
 mod device;
+pub mod display;
 mod entry;
 mod instance;
+pub mod pacing;
 pub mod prelude;
+pub mod private_data;
+pub mod query;
+pub mod ray_tracing;
+pub mod swapchain;
+pub mod sync;
+pub mod timestamp;
+pub mod upload;
 pub mod util;
+pub mod validation;
 /// Raw Vulkan bindings and types, generated from `vk.xml`
 #[macro_use]
 pub mod vk;
@@ -0,0 +1,207 @@
+//! Frame pacing with `VK_GOOGLE_display_timing` and `VK_KHR_present_wait`.
+//!
+//! [`FramePacer`] tags every present with a [`vk::PresentTimeGOOGLE`] and, when present wait is
+//! enabled, a matching [`vk::PresentIdKHR`]. Desired present times are scheduled a fixed number
+//! of refresh cycles apart, anchored to the most recent timing reported by
+//! [`google::DisplayTiming::get_past_presentation_timing()`], so that frames are displayed at an
+//! even cadence instead of as soon as they are ready.
+
+use crate::extensions::{google, khr};
+use crate::prelude::*;
+use crate::vk;
+
+/// Desired present time of `present_id`, `refresh_cycles_per_frame` refresh cycles after each
+/// previous present counting from `anchor`. Returns 0, which lets the presentation engine display
+/// the image as soon as possible, until a past timing is known.
+fn desired_present_time(
+    anchor: Option<&vk::PastPresentationTimingGOOGLE>,
+    present_id: u32,
+    refresh_duration: u64,
+    refresh_cycles_per_frame: u32,
+) -> u64 {
+    match anchor {
+        Some(anchor) => {
+            let frames = u64::from(present_id.wrapping_sub(anchor.present_id));
+            anchor.actual_present_time
+                + frames * u64::from(refresh_cycles_per_frame) * refresh_duration
+        }
+        None => 0,
+    }
+}
+
+/// A present queued by [`FramePacer::queue_present()`].
+#[derive(Clone, Copy, Debug)]
+pub struct PacedPresent {
+    /// Identifies the present in [`FramePacer::wait_for_present()`] when present wait is enabled,
+    /// and truncated to 32 bits in [`vk::PastPresentationTimingGOOGLE::present_id`].
+    pub present_id: u64,
+    /// Time in nanoseconds at which the image was requested to be displayed, 0 if unconstrained.
+    pub desired_present_time: u64,
+    pub suboptimal: bool,
+}
+
+/// Schedules presents to a swapchain at a fixed multiple of the display refresh cycle.
+#[derive(Clone)]
+pub struct FramePacer {
+    display_timing: google::DisplayTiming,
+    present_wait: Option<khr::PresentWait>,
+    swapchain: vk::SwapchainKHR,
+    refresh_duration: u64,
+    refresh_cycles_per_frame: u32,
+    next_present_id: u64,
+    past_timings: Vec<vk::PastPresentationTimingGOOGLE>,
+    anchor: Option<vk::PastPresentationTimingGOOGLE>,
+}
+
+impl FramePacer {
+    /// Creates a pacer presenting to `swapchain` every `refresh_cycles_per_frame` refresh cycles.
+    /// Pass `present_wait` when `VK_KHR_present_id` and `VK_KHR_present_wait` are enabled to be
+    /// able to call [`FramePacer::wait_for_present()`].
+    pub unsafe fn new(
+        display_timing: &google::DisplayTiming,
+        present_wait: Option<&khr::PresentWait>,
+        swapchain: vk::SwapchainKHR,
+        refresh_cycles_per_frame: u32,
+    ) -> VkResult<Self> {
+        let refresh_duration = display_timing
+            .get_refresh_cycle_duration(swapchain)?
+            .refresh_duration;
+        Ok(Self {
+            display_timing: display_timing.clone(),
+            present_wait: present_wait.cloned(),
+            swapchain,
+            refresh_duration,
+            refresh_cycles_per_frame: refresh_cycles_per_frame.max(1),
+            next_present_id: 1,
+            past_timings: Vec::new(),
+            anchor: None,
+        })
+    }
+
+    /// Switches to a recreated `swapchain`, querying its refresh cycle duration and restarting
+    /// the schedule.
+    pub unsafe fn set_swapchain(&mut self, swapchain: vk::SwapchainKHR) -> VkResult<()> {
+        self.refresh_duration = self
+            .display_timing
+            .get_refresh_cycle_duration(swapchain)?
+            .refresh_duration;
+        self.swapchain = swapchain;
+        self.past_timings.clear();
+        self.anchor = None;
+        Ok(())
+    }
+
+    /// Duration of a refresh cycle of the display in nanoseconds.
+    pub fn refresh_duration(&self) -> u64 {
+        self.refresh_duration
+    }
+
+    pub fn refresh_cycles_per_frame(&self) -> u32 {
+        self.refresh_cycles_per_frame
+    }
+
+    /// Changes the frame interval for subsequent presents.
+    pub fn set_refresh_cycles_per_frame(&mut self, refresh_cycles_per_frame: u32) {
+        self.refresh_cycles_per_frame = refresh_cycles_per_frame.max(1);
+    }
+
+    /// Fetches the timings of presents that completed since the previous call and re-anchors the
+    /// schedule to the latest one.
+    pub unsafe fn update(&mut self) -> VkResult<&[vk::PastPresentationTimingGOOGLE]> {
+        self.past_timings = self
+            .display_timing
+            .get_past_presentation_timing(self.swapchain)?;
+        if let Some(latest) = self.past_timings.last() {
+            self.anchor = Some(*latest);
+        }
+        Ok(&self.past_timings)
+    }
+
+    /// Timings fetched by the last [`FramePacer::update()`].
+    pub fn past_timings(&self) -> &[vk::PastPresentationTimingGOOGLE] {
+        &self.past_timings
+    }
+
+    /// Presents `image_index` once `wait_semaphores` are signaled, requesting it to be displayed
+    /// at the next slot of the schedule.
+    pub unsafe fn queue_present(
+        &mut self,
+        swapchain_loader: &khr::Swapchain,
+        queue: vk::Queue,
+        wait_semaphores: &[vk::Semaphore],
+        image_index: u32,
+    ) -> VkResult<PacedPresent> {
+        let present_id = self.next_present_id;
+        // `VK_KHR_present_id` requires strictly increasing ids, while the timing ids are only
+        // 32 bits wide and may wrap.
+        let timing_present_id = present_id as u32;
+        let desired_present_time = desired_present_time(
+            self.anchor.as_ref(),
+            timing_present_id,
+            self.refresh_duration,
+            self.refresh_cycles_per_frame,
+        );
+
+        let swapchains = [self.swapchain];
+        let image_indices = [image_index];
+        let times = [vk::PresentTimeGOOGLE {
+            present_id: timing_present_id,
+            desired_present_time,
+        }];
+        let mut times_info = vk::PresentTimesInfoGOOGLE::builder().times(&times);
+        let present_ids = [present_id];
+        let mut present_id_info = vk::PresentIdKHR::builder().present_ids(&present_ids);
+        let mut present_info = vk::PresentInfoKHR::builder()
+            .wait_semaphores(wait_semaphores)
+            .swapchains(&swapchains)
+            .image_indices(&image_indices)
+            .push_next(&mut times_info);
+        if self.present_wait.is_some() {
+            present_info = present_info.push_next(&mut present_id_info);
+        }
+
+        let suboptimal = swapchain_loader.queue_present(queue, &present_info)?;
+        self.next_present_id += 1;
+        Ok(PacedPresent {
+            present_id,
+            desired_present_time,
+            suboptimal,
+        })
+    }
+
+    /// Waits until the present with `present_id` has been displayed.
+    ///
+    /// Returns [`vk::Result::ERROR_FEATURE_NOT_PRESENT`] when the pacer was created without
+    /// [`khr::PresentWait`].
+    pub unsafe fn wait_for_present(&self, present_id: u64, timeout: u64) -> VkResult<()> {
+        match &self.present_wait {
+            Some(present_wait) => {
+                present_wait.wait_for_present(self.swapchain, present_id, timeout)
+            }
+            None => Err(vk::Result::ERROR_FEATURE_NOT_PRESENT),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schedule_from_anchor() {
+        let anchor = vk::PastPresentationTimingGOOGLE {
+            present_id: 10,
+            actual_present_time: 1_000_000,
+            ..Default::default()
+        };
+        assert_eq!(desired_present_time(None, 12, 16_666_667, 1), 0);
+        assert_eq!(
+            desired_present_time(Some(&anchor), 12, 16_666_667, 1),
+            1_000_000 + 2 * 16_666_667
+        );
+        assert_eq!(
+            desired_present_time(Some(&anchor), 13, 8_333_333, 2),
+            1_000_000 + 6 * 8_333_333
+        );
+    }
+}
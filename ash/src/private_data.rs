@@ -0,0 +1,181 @@
+//! Attaching Rust values to Vulkan objects through `VK_EXT_private_data`.
+//!
+//! A private data slot stores one 64-bit payload per object. [`TypedPrivateDataSlot`] owns a
+//! slot and uses that payload as an index into a table of boxed values, so engine-side metadata
+//! can be looked up from any handle without a hash map.
+
+use crate::extensions::ext::PrivateData;
+use crate::prelude::*;
+use crate::vk;
+
+/// Boxed values addressed by non-zero payloads; 0 is the payload of objects without a value.
+struct ValueTable<T> {
+    values: Vec<Option<Box<T>>>,
+    free: Vec<usize>,
+}
+
+impl<T> ValueTable<T> {
+    fn new() -> Self {
+        Self {
+            values: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    fn insert(&mut self, value: T) -> u64 {
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.values[index] = Some(Box::new(value));
+                index
+            }
+            None => {
+                self.values.push(Some(Box::new(value)));
+                self.values.len() - 1
+            }
+        };
+        index as u64 + 1
+    }
+
+    fn index(payload: u64) -> Option<usize> {
+        payload.checked_sub(1).map(|index| index as usize)
+    }
+
+    fn get(&self, payload: u64) -> Option<&T> {
+        let index = Self::index(payload)?;
+        self.values.get(index)?.as_deref()
+    }
+
+    fn get_mut(&mut self, payload: u64) -> Option<&mut T> {
+        let index = Self::index(payload)?;
+        self.values.get_mut(index)?.as_deref_mut()
+    }
+
+    fn remove(&mut self, payload: u64) -> Option<T> {
+        let index = Self::index(payload)?;
+        let value = self.values.get_mut(index)?.take()?;
+        self.free.push(index);
+        Some(*value)
+    }
+
+    fn len(&self) -> usize {
+        self.values.len() - self.free.len()
+    }
+}
+
+/// A private data slot holding a `T` per object.
+///
+/// The payload of the slot is managed by this type and must not be written through
+/// [`PrivateData::set_private_data()`]. Values are not dropped when their object is destroyed;
+/// call [`TypedPrivateDataSlot::remove()`] first, or they are released by
+/// [`TypedPrivateDataSlot::destroy()`].
+pub struct TypedPrivateDataSlot<T> {
+    slot: vk::PrivateDataSlotEXT,
+    values: ValueTable<T>,
+}
+
+impl<T> TypedPrivateDataSlot<T> {
+    pub unsafe fn new(
+        private_data: &PrivateData,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<Self> {
+        let slot = private_data.create_private_data_slot(
+            &vk::PrivateDataSlotCreateInfoEXT::default(),
+            allocation_callbacks,
+        )?;
+        Ok(Self {
+            slot,
+            values: ValueTable::new(),
+        })
+    }
+
+    pub fn slot(&self) -> vk::PrivateDataSlotEXT {
+        self.slot
+    }
+
+    /// Number of objects that currently have a value.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Attaches `value` to `object` and returns the value it replaces.
+    pub unsafe fn insert<H: vk::Handle + Copy>(
+        &mut self,
+        private_data: &PrivateData,
+        object: H,
+        value: T,
+    ) -> VkResult<Option<T>> {
+        let previous = private_data.get_private_data(object, self.slot);
+        let payload = self.values.insert(value);
+        if let Err(err) = private_data.set_private_data(object, self.slot, payload) {
+            self.values.remove(payload);
+            return Err(err);
+        }
+        Ok(self.values.remove(previous))
+    }
+
+    pub unsafe fn get<H: vk::Handle>(&self, private_data: &PrivateData, object: H) -> Option<&T> {
+        self.values
+            .get(private_data.get_private_data(object, self.slot))
+    }
+
+    pub unsafe fn get_mut<H: vk::Handle>(
+        &mut self,
+        private_data: &PrivateData,
+        object: H,
+    ) -> Option<&mut T> {
+        self.values
+            .get_mut(private_data.get_private_data(object, self.slot))
+    }
+
+    /// Detaches and returns the value of `object`.
+    pub unsafe fn remove<H: vk::Handle + Copy>(
+        &mut self,
+        private_data: &PrivateData,
+        object: H,
+    ) -> VkResult<Option<T>> {
+        let payload = private_data.get_private_data(object, self.slot);
+        if payload == 0 {
+            return Ok(None);
+        }
+        private_data.set_private_data(object, self.slot, 0)?;
+        Ok(self.values.remove(payload))
+    }
+
+    /// Destroys the slot and drops all values still attached to objects.
+    pub unsafe fn destroy(
+        self,
+        private_data: &PrivateData,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) {
+        private_data.destroy_private_data_slot(self.slot, allocation_callbacks);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_table_reuses_payloads() {
+        let mut table = ValueTable::new();
+        let a = table.insert("a");
+        let b = table.insert("b");
+        assert_ne!(a, 0);
+        assert_ne!(a, b);
+        assert_eq!(table.get(0), None);
+        assert_eq!(table.get(b), Some(&"b"));
+
+        assert_eq!(table.remove(a), Some("a"));
+        assert_eq!(table.remove(a), None);
+        assert_eq!(table.len(), 1);
+        *table.get_mut(b).unwrap() = "c";
+        assert_eq!(table.insert("d"), a);
+        assert_eq!(table.get(a), Some(&"d"));
+        assert_eq!(table.get(b), Some(&"c"));
+        assert_eq!(table.get(b + 10), None);
+    }
+}
@@ -0,0 +1,266 @@
+//! Typed readers for [`Device::get_query_pool_results()`].
+//!
+//! Every reader returns one entry per query. With `wait` set, the call blocks until all results
+//! are available and every entry is [`Some`]. Otherwise results are read together with their
+//! availability and queries that have not completed yet are returned as [`None`].
+
+use crate::prelude::*;
+use crate::vk;
+use crate::Device;
+
+/// Reads `query_count` results of `values_per_query` 64-bit values each, decoding the values of
+/// every available query with `decode`.
+unsafe fn read_query_results<T>(
+    device: &Device,
+    query_pool: vk::QueryPool,
+    first_query: u32,
+    query_count: u32,
+    values_per_query: usize,
+    wait: bool,
+    decode: impl Fn(&[u64]) -> T,
+) -> VkResult<Vec<Option<T>>> {
+    let (stride, flags) = if wait {
+        (
+            values_per_query,
+            vk::QueryResultFlags::TYPE_64 | vk::QueryResultFlags::WAIT,
+        )
+    } else {
+        (
+            values_per_query + 1,
+            vk::QueryResultFlags::TYPE_64 | vk::QueryResultFlags::WITH_AVAILABILITY,
+        )
+    };
+    let mut data = vec![0u64; stride * query_count as usize];
+    let stride_bytes = (stride * std::mem::size_of::<u64>()) as vk::DeviceSize;
+    let err_code = device.fp_v1_0().get_query_pool_results(
+        device.handle(),
+        query_pool,
+        first_query,
+        query_count,
+        data.len() * std::mem::size_of::<u64>(),
+        data.as_mut_ptr().cast(),
+        stride_bytes,
+        flags,
+    );
+    match err_code {
+        vk::Result::SUCCESS => {}
+        // Some results are unavailable, which is reported through the availability values.
+        vk::Result::NOT_READY if !wait => {}
+        _ => return Err(err_code),
+    }
+
+    Ok(data
+        .chunks_exact(stride)
+        .map(|query| {
+            let (values, availability) = query.split_at(values_per_query);
+            match availability.first() {
+                Some(0) => None,
+                _ => Some(decode(values)),
+            }
+        })
+        .collect())
+}
+
+/// Reads the number of samples that passed the depth and stencil tests for every
+/// [`vk::QueryType::OCCLUSION`] query.
+pub unsafe fn get_occlusion_query_results(
+    device: &Device,
+    query_pool: vk::QueryPool,
+    first_query: u32,
+    query_count: u32,
+    wait: bool,
+) -> VkResult<Vec<Option<u64>>> {
+    read_query_results(
+        device,
+        query_pool,
+        first_query,
+        query_count,
+        1,
+        wait,
+        |values| values[0],
+    )
+}
+
+/// Reads the raw tick values of [`vk::QueryType::TIMESTAMP`] queries. Multiply tick differences
+/// by [`vk::PhysicalDeviceLimits::timestamp_period`] to get nanoseconds.
+pub unsafe fn get_timestamp_query_results(
+    device: &Device,
+    query_pool: vk::QueryPool,
+    first_query: u32,
+    query_count: u32,
+    wait: bool,
+) -> VkResult<Vec<Option<u64>>> {
+    read_query_results(
+        device,
+        query_pool,
+        first_query,
+        query_count,
+        1,
+        wait,
+        |values| values[0],
+    )
+}
+
+/// Results of a [`vk::QueryType::PIPELINE_STATISTICS`] query, with a value for every statistic
+/// the query pool was created with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PipelineStatistics {
+    pub input_assembly_vertices: Option<u64>,
+    pub input_assembly_primitives: Option<u64>,
+    pub vertex_shader_invocations: Option<u64>,
+    pub geometry_shader_invocations: Option<u64>,
+    pub geometry_shader_primitives: Option<u64>,
+    pub clipping_invocations: Option<u64>,
+    pub clipping_primitives: Option<u64>,
+    pub fragment_shader_invocations: Option<u64>,
+    pub tessellation_control_shader_patches: Option<u64>,
+    pub tessellation_evaluation_shader_invocations: Option<u64>,
+    pub compute_shader_invocations: Option<u64>,
+}
+
+impl PipelineStatistics {
+    /// Decodes the values written for `statistics`, which are ordered by increasing bit.
+    /// Values of statistics that are not known to this struct are skipped.
+    pub fn decode(statistics: vk::QueryPipelineStatisticFlags, values: &[u64]) -> Self {
+        let mut result = Self::default();
+        let mut values = values.iter().copied();
+        let mut bits = statistics.as_raw();
+        while bits != 0 {
+            let bit = bits & bits.wrapping_neg();
+            bits &= !bit;
+            let value = values.next();
+            let field = match vk::QueryPipelineStatisticFlags::from_raw(bit) {
+                vk::QueryPipelineStatisticFlags::INPUT_ASSEMBLY_VERTICES => {
+                    &mut result.input_assembly_vertices
+                }
+                vk::QueryPipelineStatisticFlags::INPUT_ASSEMBLY_PRIMITIVES => {
+                    &mut result.input_assembly_primitives
+                }
+                vk::QueryPipelineStatisticFlags::VERTEX_SHADER_INVOCATIONS => {
+                    &mut result.vertex_shader_invocations
+                }
+                vk::QueryPipelineStatisticFlags::GEOMETRY_SHADER_INVOCATIONS => {
+                    &mut result.geometry_shader_invocations
+                }
+                vk::QueryPipelineStatisticFlags::GEOMETRY_SHADER_PRIMITIVES => {
+                    &mut result.geometry_shader_primitives
+                }
+                vk::QueryPipelineStatisticFlags::CLIPPING_INVOCATIONS => {
+                    &mut result.clipping_invocations
+                }
+                vk::QueryPipelineStatisticFlags::CLIPPING_PRIMITIVES => {
+                    &mut result.clipping_primitives
+                }
+                vk::QueryPipelineStatisticFlags::FRAGMENT_SHADER_INVOCATIONS => {
+                    &mut result.fragment_shader_invocations
+                }
+                vk::QueryPipelineStatisticFlags::TESSELLATION_CONTROL_SHADER_PATCHES => {
+                    &mut result.tessellation_control_shader_patches
+                }
+                vk::QueryPipelineStatisticFlags::TESSELLATION_EVALUATION_SHADER_INVOCATIONS => {
+                    &mut result.tessellation_evaluation_shader_invocations
+                }
+                vk::QueryPipelineStatisticFlags::COMPUTE_SHADER_INVOCATIONS => {
+                    &mut result.compute_shader_invocations
+                }
+                _ => continue,
+            };
+            *field = value;
+        }
+        result
+    }
+}
+
+/// Reads [`vk::QueryType::PIPELINE_STATISTICS`] queries from a pool created with
+/// `pipeline_statistics` set to `statistics`.
+pub unsafe fn get_pipeline_statistics_query_results(
+    device: &Device,
+    query_pool: vk::QueryPool,
+    first_query: u32,
+    query_count: u32,
+    statistics: vk::QueryPipelineStatisticFlags,
+    wait: bool,
+) -> VkResult<Vec<Option<PipelineStatistics>>> {
+    assert!(!statistics.is_empty());
+    read_query_results(
+        device,
+        query_pool,
+        first_query,
+        query_count,
+        statistics.as_raw().count_ones() as usize,
+        wait,
+        |values| PipelineStatistics::decode(statistics, values),
+    )
+}
+
+/// Results of a [`vk::QueryType::TRANSFORM_FEEDBACK_STREAM_EXT`] query.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TransformFeedbackStatistics {
+    pub primitives_written: u64,
+    pub primitives_needed: u64,
+}
+
+/// Reads [`vk::QueryType::TRANSFORM_FEEDBACK_STREAM_EXT`] queries.
+pub unsafe fn get_transform_feedback_query_results(
+    device: &Device,
+    query_pool: vk::QueryPool,
+    first_query: u32,
+    query_count: u32,
+    wait: bool,
+) -> VkResult<Vec<Option<TransformFeedbackStatistics>>> {
+    read_query_results(
+        device,
+        query_pool,
+        first_query,
+        query_count,
+        2,
+        wait,
+        |values| TransformFeedbackStatistics {
+            primitives_written: values[0],
+            primitives_needed: values[1],
+        },
+    )
+}
+
+/// Reads acceleration structure size queries, such as
+/// [`vk::QueryType::ACCELERATION_STRUCTURE_COMPACTED_SIZE_KHR`] and
+/// [`vk::QueryType::ACCELERATION_STRUCTURE_SERIALIZATION_SIZE_KHR`].
+pub unsafe fn get_acceleration_structure_size_query_results(
+    device: &Device,
+    query_pool: vk::QueryPool,
+    first_query: u32,
+    query_count: u32,
+    wait: bool,
+) -> VkResult<Vec<Option<vk::DeviceSize>>> {
+    read_query_results(
+        device,
+        query_pool,
+        first_query,
+        query_count,
+        1,
+        wait,
+        |values| values[0],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_pipeline_statistics() {
+        let statistics = vk::QueryPipelineStatisticFlags::VERTEX_SHADER_INVOCATIONS
+            | vk::QueryPipelineStatisticFlags::INPUT_ASSEMBLY_VERTICES
+            | vk::QueryPipelineStatisticFlags::COMPUTE_SHADER_INVOCATIONS;
+        let decoded = PipelineStatistics::decode(statistics, &[3, 6, 9]);
+        assert_eq!(
+            decoded,
+            PipelineStatistics {
+                input_assembly_vertices: Some(3),
+                vertex_shader_invocations: Some(6),
+                compute_shader_invocations: Some(9),
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,728 @@
+use crate::alloc::{Allocation, Allocator, MemoryUsage};
+use crate::extensions::khr;
+use crate::prelude::*;
+use crate::query::get_acceleration_structure_size_query_results;
+use crate::vk;
+use crate::Device;
+use std::mem;
+
+fn align_up(value: u64, alignment: u64) -> u64 {
+    value + (alignment - value % alignment) % alignment
+}
+
+/// Geometry of an acceleration structure build.
+///
+/// Addresses point to device memory that has been written before the build executes, and are
+/// `0` for optional data.
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum Geometry {
+    /// `triangle_count` triangles. `index_type` is [`vk::IndexType::NONE_KHR`] for non-indexed
+    /// geometry and `transform_data` optionally points to a [`vk::TransformMatrixKHR`].
+    Triangles {
+        vertex_format: vk::Format,
+        vertex_data: vk::DeviceAddress,
+        vertex_stride: vk::DeviceSize,
+        max_vertex: u32,
+        index_type: vk::IndexType,
+        index_data: vk::DeviceAddress,
+        transform_data: vk::DeviceAddress,
+        triangle_count: u32,
+        flags: vk::GeometryFlagsKHR,
+    },
+    /// `aabb_count` [`vk::AabbPositionsKHR`]s, `stride` bytes apart.
+    Aabbs {
+        data: vk::DeviceAddress,
+        stride: vk::DeviceSize,
+        aabb_count: u32,
+        flags: vk::GeometryFlagsKHR,
+    },
+    /// `instance_count` tightly packed [`vk::AccelerationStructureInstanceKHR`]s.
+    Instances {
+        data: vk::DeviceAddress,
+        instance_count: u32,
+        flags: vk::GeometryFlagsKHR,
+    },
+}
+
+impl Geometry {
+    fn primitive_count(&self) -> u32 {
+        match *self {
+            Self::Triangles { triangle_count, .. } => triangle_count,
+            Self::Aabbs { aabb_count, .. } => aabb_count,
+            Self::Instances { instance_count, .. } => instance_count,
+        }
+    }
+
+    fn to_vk(self) -> vk::AccelerationStructureGeometryKHR {
+        let (geometry_type, geometry, flags) = match self {
+            Self::Triangles {
+                vertex_format,
+                vertex_data,
+                vertex_stride,
+                max_vertex,
+                index_type,
+                index_data,
+                transform_data,
+                flags,
+                ..
+            } => (
+                vk::GeometryTypeKHR::TRIANGLES,
+                vk::AccelerationStructureGeometryDataKHR {
+                    triangles: vk::AccelerationStructureGeometryTrianglesDataKHR::builder()
+                        .vertex_format(vertex_format)
+                        .vertex_data(vk::DeviceOrHostAddressConstKHR {
+                            device_address: vertex_data,
+                        })
+                        .vertex_stride(vertex_stride)
+                        .max_vertex(max_vertex)
+                        .index_type(index_type)
+                        .index_data(vk::DeviceOrHostAddressConstKHR {
+                            device_address: index_data,
+                        })
+                        .transform_data(vk::DeviceOrHostAddressConstKHR {
+                            device_address: transform_data,
+                        })
+                        .build(),
+                },
+                flags,
+            ),
+            Self::Aabbs {
+                data,
+                stride,
+                flags,
+                ..
+            } => (
+                vk::GeometryTypeKHR::AABBS,
+                vk::AccelerationStructureGeometryDataKHR {
+                    aabbs: vk::AccelerationStructureGeometryAabbsDataKHR::builder()
+                        .data(vk::DeviceOrHostAddressConstKHR {
+                            device_address: data,
+                        })
+                        .stride(stride)
+                        .build(),
+                },
+                flags,
+            ),
+            Self::Instances { data, flags, .. } => (
+                vk::GeometryTypeKHR::INSTANCES,
+                vk::AccelerationStructureGeometryDataKHR {
+                    instances: vk::AccelerationStructureGeometryInstancesDataKHR::builder()
+                        .array_of_pointers(false)
+                        .data(vk::DeviceOrHostAddressConstKHR {
+                            device_address: data,
+                        })
+                        .build(),
+                },
+                flags,
+            ),
+        };
+        vk::AccelerationStructureGeometryKHR::builder()
+            .geometry_type(geometry_type)
+            .geometry(geometry)
+            .flags(flags)
+            .build()
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+struct BuildDesc {
+    ty: vk::AccelerationStructureTypeKHR,
+    flags: vk::BuildAccelerationStructureFlagsKHR,
+    geometries: Vec<Geometry>,
+}
+
+/// Records a batch of acceleration structure builds into a single command buffer.
+///
+/// [`AccelerationStructureBuilder::record()`] sizes every structure, allocates its buffer and a
+/// scratch buffer shared by the whole batch, and records the builds. Bottom-level structures are
+/// built before top-level structures, with a barrier in between, so that a top-level structure
+/// may reference bottom-level structures of the same batch. A barrier after the builds makes the
+/// results available to later builds and ray tracing shaders.
+///
+/// Structures built with [`vk::BuildAccelerationStructureFlagsKHR::ALLOW_COMPACTION`] have their
+/// compacted size queried as part of the batch. Once that command buffer has completed,
+/// [`AccelerationStructureBuild::record_compaction()`] records copies into smaller structures,
+/// typically in a second submission. Compaction changes the handle and device address of a
+/// structure, so bottom-level structures are not compacted in a batch that also builds top-level
+/// structures, which may reference them. Build and compact them in a batch of their own first.
+///
+/// ```no_run
+/// # use ash::{alloc::Allocator, extensions::khr, vk};
+/// # use ash::ray_tracing::{AccelerationStructureBuilder, Geometry};
+/// # unsafe fn example(
+/// #     acceleration_structure: &khr::AccelerationStructure,
+/// #     allocator: &mut Allocator,
+/// #     properties: &vk::PhysicalDeviceAccelerationStructurePropertiesKHR,
+/// #     command_buffer: vk::CommandBuffer,
+/// #     vertex_data: vk::DeviceAddress,
+/// # ) -> ash::prelude::VkResult<()> {
+/// let mut builder = AccelerationStructureBuilder::new(properties);
+/// let blas = builder.add(
+///     vk::BuildAccelerationStructureFlagsKHR::PREFER_FAST_TRACE
+///         | vk::BuildAccelerationStructureFlagsKHR::ALLOW_COMPACTION,
+///     &[Geometry::Triangles {
+///         vertex_format: vk::Format::R32G32B32_SFLOAT,
+///         vertex_data,
+///         vertex_stride: 12,
+///         max_vertex: 2,
+///         index_type: vk::IndexType::NONE_KHR,
+///         index_data: 0,
+///         transform_data: 0,
+///         triangle_count: 1,
+///         flags: vk::GeometryFlagsKHR::OPAQUE,
+///     }],
+/// );
+/// let mut build = builder.record(acceleration_structure, allocator, command_buffer)?;
+/// // Submit `command_buffer` and wait for it to complete.
+/// build.record_compaction(acceleration_structure, allocator, command_buffer)?;
+/// // Submit `command_buffer` again and wait for it to complete.
+/// let structures = build.finish(acceleration_structure, allocator);
+/// let blas_address = structures[blas].device_address();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct AccelerationStructureBuilder {
+    scratch_offset_alignment: u64,
+    builds: Vec<BuildDesc>,
+}
+
+impl AccelerationStructureBuilder {
+    /// Takes the scratch alignment from `properties`, as returned by
+    /// [`khr::AccelerationStructure::get_properties()`].
+    pub fn new(properties: &vk::PhysicalDeviceAccelerationStructurePropertiesKHR) -> Self {
+        Self {
+            scratch_offset_alignment: u64::from(
+                properties
+                    .min_acceleration_structure_scratch_offset_alignment
+                    .max(1),
+            ),
+            builds: Vec::new(),
+        }
+    }
+
+    /// Adds a structure built from `geometries` and returns its index in
+    /// [`AccelerationStructureBuild::acceleration_structures()`]. A single
+    /// [`Geometry::Instances`] builds a top-level structure, any number of triangle and AABB
+    /// geometries build a bottom-level structure.
+    pub fn add(
+        &mut self,
+        flags: vk::BuildAccelerationStructureFlagsKHR,
+        geometries: &[Geometry],
+    ) -> usize {
+        let instances = geometries
+            .iter()
+            .filter(|geometry| matches!(geometry, Geometry::Instances { .. }))
+            .count();
+        let ty = if instances == 0 {
+            vk::AccelerationStructureTypeKHR::BOTTOM_LEVEL
+        } else {
+            assert_eq!(
+                geometries.len(),
+                1,
+                "top-level structures are built from a single instance geometry"
+            );
+            vk::AccelerationStructureTypeKHR::TOP_LEVEL
+        };
+        self.builds.push(BuildDesc {
+            ty,
+            flags,
+            geometries: geometries.to_vec(),
+        });
+        self.builds.len() - 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.builds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.builds.is_empty()
+    }
+
+    /// Indices of the builds that are compacted: those allowing compaction, except bottom-level
+    /// structures when the batch contains a top-level structure.
+    fn compacted_builds(&self) -> Vec<usize> {
+        let has_top_level = self
+            .builds
+            .iter()
+            .any(|build| build.ty == vk::AccelerationStructureTypeKHR::TOP_LEVEL);
+        (0..self.builds.len())
+            .filter(|&i| {
+                let build = &self.builds[i];
+                build
+                    .flags
+                    .contains(vk::BuildAccelerationStructureFlagsKHR::ALLOW_COMPACTION)
+                    && !(has_top_level
+                        && build.ty == vk::AccelerationStructureTypeKHR::BOTTOM_LEVEL)
+            })
+            .collect()
+    }
+
+    /// Creates the structures and their buffers and records the builds into `command_buffer`.
+    /// `allocator` must be created with [`crate::alloc::AllocatorCreateDesc::buffer_device_address`].
+    pub unsafe fn record(
+        &self,
+        acceleration_structure: &khr::AccelerationStructure,
+        allocator: &mut Allocator,
+        command_buffer: vk::CommandBuffer,
+    ) -> VkResult<AccelerationStructureBuild> {
+        let mut build = AccelerationStructureBuild {
+            structures: Vec::new(),
+            retired: Vec::new(),
+            scratch: None,
+            query_pool: vk::QueryPool::null(),
+            compaction_queries: Vec::new(),
+        };
+        match build.record_builds(self, acceleration_structure, allocator, command_buffer) {
+            Ok(()) => Ok(build),
+            Err(err) => {
+                build.destroy(acceleration_structure, allocator);
+                Err(err)
+            }
+        }
+    }
+}
+
+/// An acceleration structure together with the buffer and memory backing it.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct BuiltAccelerationStructure {
+    acceleration_structure: vk::AccelerationStructureKHR,
+    ty: vk::AccelerationStructureTypeKHR,
+    size: vk::DeviceSize,
+    buffer: vk::Buffer,
+    allocation: Allocation,
+    device_address: vk::DeviceAddress,
+}
+
+impl BuiltAccelerationStructure {
+    pub fn handle(&self) -> vk::AccelerationStructureKHR {
+        self.acceleration_structure
+    }
+
+    pub fn ty(&self) -> vk::AccelerationStructureTypeKHR {
+        self.ty
+    }
+
+    pub fn buffer(&self) -> vk::Buffer {
+        self.buffer
+    }
+
+    pub fn size(&self) -> vk::DeviceSize {
+        self.size
+    }
+
+    /// Address to reference this structure with in
+    /// [`vk::AccelerationStructureInstanceKHR::acceleration_structure_reference`].
+    pub fn device_address(&self) -> vk::DeviceAddress {
+        self.device_address
+    }
+
+    unsafe fn new(
+        acceleration_structure: &khr::AccelerationStructure,
+        allocator: &mut Allocator,
+        ty: vk::AccelerationStructureTypeKHR,
+        size: vk::DeviceSize,
+    ) -> VkResult<Self> {
+        let (buffer, allocation) = create_buffer(
+            allocator,
+            size,
+            vk::BufferUsageFlags::ACCELERATION_STRUCTURE_STORAGE_KHR
+                | vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS,
+        )?;
+        let create_info = vk::AccelerationStructureCreateInfoKHR::builder()
+            .buffer(buffer)
+            .size(size)
+            .ty(ty);
+        let handle = match acceleration_structure.create_acceleration_structure(&create_info, None)
+        {
+            Ok(handle) => handle,
+            Err(err) => {
+                allocator.device().destroy_buffer(buffer, None);
+                allocator.free(allocation);
+                return Err(err);
+            }
+        };
+        let address_info =
+            vk::AccelerationStructureDeviceAddressInfoKHR::builder().acceleration_structure(handle);
+        Ok(Self {
+            acceleration_structure: handle,
+            ty,
+            size,
+            buffer,
+            allocation,
+            device_address: acceleration_structure
+                .get_acceleration_structure_device_address(&address_info),
+        })
+    }
+
+    pub unsafe fn destroy(
+        self,
+        acceleration_structure: &khr::AccelerationStructure,
+        allocator: &mut Allocator,
+    ) {
+        acceleration_structure.destroy_acceleration_structure(self.acceleration_structure, None);
+        allocator.device().destroy_buffer(self.buffer, None);
+        allocator.free(self.allocation);
+    }
+}
+
+unsafe fn create_buffer(
+    allocator: &mut Allocator,
+    size: vk::DeviceSize,
+    usage: vk::BufferUsageFlags,
+) -> VkResult<(vk::Buffer, Allocation)> {
+    let buffer_info = vk::BufferCreateInfo::builder()
+        .size(size)
+        .usage(usage)
+        .sharing_mode(vk::SharingMode::EXCLUSIVE);
+    let buffer = allocator.device().create_buffer(&buffer_info, None)?;
+    match allocator.allocate_for_buffer(buffer, MemoryUsage::GpuOnly) {
+        Ok(allocation) => Ok((buffer, allocation)),
+        Err(err) => {
+            allocator.device().destroy_buffer(buffer, None);
+            Err(err)
+        }
+    }
+}
+
+/// Makes acceleration structure writes visible to later builds, copies and ray tracing shaders.
+unsafe fn cmd_acceleration_structure_barrier(device: &Device, command_buffer: vk::CommandBuffer) {
+    let barrier = vk::MemoryBarrier::builder()
+        .src_access_mask(vk::AccessFlags::ACCELERATION_STRUCTURE_WRITE_KHR)
+        .dst_access_mask(vk::AccessFlags::ACCELERATION_STRUCTURE_READ_KHR);
+    device.cmd_pipeline_barrier(
+        command_buffer,
+        vk::PipelineStageFlags::ACCELERATION_STRUCTURE_BUILD_KHR,
+        vk::PipelineStageFlags::ACCELERATION_STRUCTURE_BUILD_KHR
+            | vk::PipelineStageFlags::RAY_TRACING_SHADER_KHR,
+        vk::DependencyFlags::empty(),
+        &[*barrier],
+        &[],
+        &[],
+    );
+}
+
+/// Resources of a batch recorded by [`AccelerationStructureBuilder::record()`].
+///
+/// The scratch buffer, compaction queries and structures replaced by compaction are kept alive
+/// until [`AccelerationStructureBuild::finish()`], which must only be called once the recorded
+/// command buffers have completed.
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct AccelerationStructureBuild {
+    structures: Vec<BuiltAccelerationStructure>,
+    retired: Vec<BuiltAccelerationStructure>,
+    scratch: Option<(vk::Buffer, Allocation)>,
+    query_pool: vk::QueryPool,
+    /// Index of the structure every compacted size query was written for.
+    compaction_queries: Vec<usize>,
+}
+
+impl AccelerationStructureBuild {
+    unsafe fn record_builds(
+        &mut self,
+        builder: &AccelerationStructureBuilder,
+        acceleration_structure: &khr::AccelerationStructure,
+        allocator: &mut Allocator,
+        command_buffer: vk::CommandBuffer,
+    ) -> VkResult<()> {
+        if builder.is_empty() {
+            return Ok(());
+        }
+        let device = allocator.device().clone();
+        let alignment = builder.scratch_offset_alignment;
+
+        let geometries = builder
+            .builds
+            .iter()
+            .map(|build| {
+                build
+                    .geometries
+                    .iter()
+                    .map(|geometry| geometry.to_vk())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let ranges = builder
+            .builds
+            .iter()
+            .map(|build| {
+                build
+                    .geometries
+                    .iter()
+                    .map(|geometry| vk::AccelerationStructureBuildRangeInfoKHR {
+                        primitive_count: geometry.primitive_count(),
+                        ..Default::default()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut infos = builder
+            .builds
+            .iter()
+            .zip(&geometries)
+            .map(|(build, geometries)| {
+                vk::AccelerationStructureBuildGeometryInfoKHR::builder()
+                    .ty(build.ty)
+                    .flags(build.flags)
+                    .mode(vk::BuildAccelerationStructureModeKHR::BUILD)
+                    .geometries(geometries)
+                    .build()
+            })
+            .collect::<Vec<_>>();
+
+        let mut scratch_offsets = Vec::with_capacity(infos.len());
+        let mut scratch_size = 0;
+        for (info, ranges) in infos.iter().zip(&ranges) {
+            let primitive_counts = ranges
+                .iter()
+                .map(|range| range.primitive_count)
+                .collect::<Vec<_>>();
+            let sizes = acceleration_structure.get_acceleration_structure_build_sizes(
+                vk::AccelerationStructureBuildTypeKHR::DEVICE,
+                info,
+                &primitive_counts,
+            );
+            self.structures.push(BuiltAccelerationStructure::new(
+                acceleration_structure,
+                allocator,
+                info.ty,
+                sizes.acceleration_structure_size,
+            )?);
+            let offset = align_up(scratch_size, alignment);
+            scratch_offsets.push(offset);
+            scratch_size = offset + sizes.build_scratch_size;
+        }
+
+        // The buffer address is only guaranteed to satisfy the buffer's own alignment, so leave
+        // room to align the start of the scratch memory.
+        let (scratch_buffer, scratch_allocation) = create_buffer(
+            allocator,
+            scratch_size + alignment,
+            vk::BufferUsageFlags::STORAGE_BUFFER | vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS,
+        )?;
+        self.scratch = Some((scratch_buffer, scratch_allocation));
+        let scratch_address = align_up(
+            device.get_buffer_device_address(
+                &vk::BufferDeviceAddressInfo::builder().buffer(scratch_buffer),
+            ),
+            alignment,
+        );
+        for ((info, structure), offset) in
+            infos.iter_mut().zip(&self.structures).zip(scratch_offsets)
+        {
+            info.dst_acceleration_structure = structure.acceleration_structure;
+            info.scratch_data = vk::DeviceOrHostAddressKHR {
+                device_address: scratch_address + offset,
+            };
+        }
+
+        for &ty in &[
+            vk::AccelerationStructureTypeKHR::BOTTOM_LEVEL,
+            vk::AccelerationStructureTypeKHR::TOP_LEVEL,
+        ] {
+            let indices = (0..infos.len())
+                .filter(|&i| infos[i].ty == ty)
+                .collect::<Vec<_>>();
+            if indices.is_empty() {
+                continue;
+            }
+            let level_infos = indices.iter().map(|&i| infos[i]).collect::<Vec<_>>();
+            let level_ranges = indices
+                .iter()
+                .map(|&i| ranges[i].as_slice())
+                .collect::<Vec<_>>();
+            acceleration_structure.cmd_build_acceleration_structures(
+                command_buffer,
+                &level_infos,
+                &level_ranges,
+            );
+            cmd_acceleration_structure_barrier(&device, command_buffer);
+        }
+
+        let compacted = builder.compacted_builds();
+        if !compacted.is_empty() {
+            let query_type = vk::QueryType::ACCELERATION_STRUCTURE_COMPACTED_SIZE_KHR;
+            let pool_info = vk::QueryPoolCreateInfo::builder()
+                .query_type(query_type)
+                .query_count(compacted.len() as u32);
+            self.query_pool = device.create_query_pool(&pool_info, None)?;
+            device.cmd_reset_query_pool(command_buffer, self.query_pool, 0, compacted.len() as u32);
+            let handles = compacted
+                .iter()
+                .map(|&i| self.structures[i].acceleration_structure)
+                .collect::<Vec<_>>();
+            acceleration_structure.cmd_write_acceleration_structures_properties(
+                command_buffer,
+                &handles,
+                query_type,
+                self.query_pool,
+                0,
+            );
+            self.compaction_queries = compacted;
+        }
+        Ok(())
+    }
+
+    /// The built structures, in the order they were added to the builder.
+    pub fn acceleration_structures(&self) -> &[BuiltAccelerationStructure] {
+        &self.structures
+    }
+
+    /// Replaces every structure built with
+    /// [`vk::BuildAccelerationStructureFlagsKHR::ALLOW_COMPACTION`] by a compacted copy, recorded
+    /// into `command_buffer`. Bottom-level structures are left as built when the batch contains a
+    /// top-level structure. The command buffer passed to
+    /// [`AccelerationStructureBuilder::record()`] must have completed. Does nothing when no
+    /// structure allows compaction or compaction was already recorded.
+    pub unsafe fn record_compaction(
+        &mut self,
+        acceleration_structure: &khr::AccelerationStructure,
+        allocator: &mut Allocator,
+        command_buffer: vk::CommandBuffer,
+    ) -> VkResult<()> {
+        if self.compaction_queries.is_empty() {
+            return Ok(());
+        }
+        let device = allocator.device().clone();
+        let sizes = get_acceleration_structure_size_query_results(
+            &device,
+            self.query_pool,
+            0,
+            self.compaction_queries.len() as u32,
+            true,
+        )?;
+        for (index, size) in mem::take(&mut self.compaction_queries)
+            .into_iter()
+            .zip(sizes)
+        {
+            // Waiting for the results guarantees that every query is available.
+            let size = size.unwrap();
+            let compacted = BuiltAccelerationStructure::new(
+                acceleration_structure,
+                allocator,
+                self.structures[index].ty,
+                size,
+            )?;
+            let copy_info = vk::CopyAccelerationStructureInfoKHR::builder()
+                .src(self.structures[index].acceleration_structure)
+                .dst(compacted.acceleration_structure)
+                .mode(vk::CopyAccelerationStructureModeKHR::COMPACT);
+            acceleration_structure.cmd_copy_acceleration_structure(command_buffer, &copy_info);
+            let original = mem::replace(&mut self.structures[index], compacted);
+            self.retired.push(original);
+        }
+        cmd_acceleration_structure_barrier(&device, command_buffer);
+        Ok(())
+    }
+
+    /// Releases the scratch buffer, the compaction queries and the structures replaced by
+    /// compaction, and returns the built structures. All recorded command buffers must have
+    /// completed.
+    pub unsafe fn finish(
+        mut self,
+        acceleration_structure: &khr::AccelerationStructure,
+        allocator: &mut Allocator,
+    ) -> Vec<BuiltAccelerationStructure> {
+        for structure in self.retired.drain(..) {
+            structure.destroy(acceleration_structure, allocator);
+        }
+        if let Some((buffer, allocation)) = self.scratch.take() {
+            allocator.device().destroy_buffer(buffer, None);
+            allocator.free(allocation);
+        }
+        if self.query_pool != vk::QueryPool::null() {
+            allocator.device().destroy_query_pool(self.query_pool, None);
+        }
+        self.structures
+    }
+
+    /// Destroys all resources of the batch, including the built structures.
+    pub unsafe fn destroy(
+        self,
+        acceleration_structure: &khr::AccelerationStructure,
+        allocator: &mut Allocator,
+    ) {
+        for structure in self.finish(acceleration_structure, allocator) {
+            structure.destroy(acceleration_structure, allocator);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn structure_types() {
+        let properties = vk::PhysicalDeviceAccelerationStructurePropertiesKHR::default();
+        let mut builder = AccelerationStructureBuilder::new(&properties);
+        let aabbs = Geometry::Aabbs {
+            data: 0x1000,
+            stride: 24,
+            aabb_count: 4,
+            flags: vk::GeometryFlagsKHR::empty(),
+        };
+        let instances = Geometry::Instances {
+            data: 0x2000,
+            instance_count: 2,
+            flags: vk::GeometryFlagsKHR::empty(),
+        };
+        let flags = vk::BuildAccelerationStructureFlagsKHR::empty();
+        assert_eq!(builder.add(flags, &[aabbs, aabbs]), 0);
+        assert_eq!(builder.add(flags, &[instances]), 1);
+        assert!(builder.builds[0].ty == vk::AccelerationStructureTypeKHR::BOTTOM_LEVEL);
+        assert!(builder.builds[1].ty == vk::AccelerationStructureTypeKHR::TOP_LEVEL);
+
+        let geometry = instances.to_vk();
+        assert!(geometry.geometry_type == vk::GeometryTypeKHR::INSTANCES);
+        assert_eq!(
+            unsafe { geometry.geometry.instances.data.device_address },
+            0x2000
+        );
+        assert_eq!(aabbs.primitive_count(), 4);
+    }
+
+    #[test]
+    fn no_bottom_level_compaction_with_top_level() {
+        let properties = vk::PhysicalDeviceAccelerationStructurePropertiesKHR::default();
+        let mut builder = AccelerationStructureBuilder::new(&properties);
+        let aabbs = Geometry::Aabbs {
+            data: 0x1000,
+            stride: 24,
+            aabb_count: 4,
+            flags: vk::GeometryFlagsKHR::empty(),
+        };
+        let instances = Geometry::Instances {
+            data: 0x2000,
+            instance_count: 1,
+            flags: vk::GeometryFlagsKHR::empty(),
+        };
+        let compact = vk::BuildAccelerationStructureFlagsKHR::ALLOW_COMPACTION;
+        builder.add(compact, &[aabbs]);
+        builder.add(vk::BuildAccelerationStructureFlagsKHR::empty(), &[aabbs]);
+        assert_eq!(builder.compacted_builds(), [0]);
+
+        // The top-level structure may reference the first structure, so it must keep its address.
+        builder.add(compact, &[instances]);
+        assert_eq!(builder.compacted_builds(), [2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn mixed_instances() {
+        let properties = vk::PhysicalDeviceAccelerationStructurePropertiesKHR::default();
+        let instances = Geometry::Instances {
+            data: 0,
+            instance_count: 1,
+            flags: vk::GeometryFlagsKHR::empty(),
+        };
+        AccelerationStructureBuilder::new(&properties).add(
+            vk::BuildAccelerationStructureFlagsKHR::empty(),
+            &[instances, instances],
+        );
+    }
+}
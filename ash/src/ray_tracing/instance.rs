@@ -0,0 +1,125 @@
+use crate::alloc::{Allocation, Allocator};
+use crate::prelude::*;
+use crate::vk;
+use std::mem;
+use std::ptr;
+
+/// Size of a serialized [`vk::AccelerationStructureInstanceKHR`], which is also the stride of
+/// the instance array read by top-level builds.
+pub const INSTANCE_SIZE: usize = mem::size_of::<vk::AccelerationStructureInstanceKHR>();
+
+/// Alignment required of the device address of an instance array.
+pub const INSTANCE_ALIGNMENT: vk::DeviceSize = 16;
+
+/// Serializes `instances` into the start of `dst` and returns the number of bytes written.
+///
+/// # Panics
+///
+/// Panics when `dst` is smaller than `instances.len() * INSTANCE_SIZE` bytes.
+pub fn write_instances(
+    dst: &mut [u8],
+    instances: &[vk::AccelerationStructureInstanceKHR],
+) -> usize {
+    let size = instances.len() * INSTANCE_SIZE;
+    assert!(
+        dst.len() >= size,
+        "{} bytes do not fit {} instances",
+        dst.len(),
+        instances.len()
+    );
+    unsafe {
+        ptr::copy_nonoverlapping(instances.as_ptr().cast::<u8>(), dst.as_mut_ptr(), size);
+    }
+    size
+}
+
+/// Serializes `instances` into the host-visible `allocation` at `offset` and flushes the
+/// written range. The memory must not be in use by the device.
+///
+/// The buffer device address passed to [`super::Geometry::Instances`] must be a multiple of
+/// [`INSTANCE_ALIGNMENT`].
+///
+/// # Panics
+///
+/// Panics when `allocation` is not host-visible or the instances do not fit.
+pub unsafe fn write_instances_to_allocation(
+    allocator: &Allocator,
+    allocation: &Allocation,
+    offset: vk::DeviceSize,
+    instances: &[vk::AccelerationStructureInstanceKHR],
+) -> VkResult<()> {
+    let mapped = allocation
+        .mapped_ptr()
+        .expect("instance memory is not host-visible");
+    let size = (instances.len() * INSTANCE_SIZE) as vk::DeviceSize;
+    assert!(
+        offset + size <= allocation.size(),
+        "instances do not fit in the allocation"
+    );
+    let dst = std::slice::from_raw_parts_mut(
+        (mapped.as_ptr() as *mut u8).add(offset as usize),
+        size as usize,
+    );
+    write_instances(dst, instances);
+    allocator.flush(allocation, offset, size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack_instance() {
+        let mut instance = vk::AccelerationStructureInstanceKHR::new(
+            vk::TransformMatrixKHR::from_translation([1.0, 2.0, 3.0]),
+            0x00ab_cdef,
+            0x0f,
+            7,
+            vk::GeometryInstanceFlagsKHR::FORCE_OPAQUE,
+            0x1000,
+        );
+        assert_eq!(instance.instance_custom_index(), 0x00ab_cdef);
+        assert_eq!(instance.mask(), 0x0f);
+        assert_eq!(instance.shader_binding_table_record_offset(), 7);
+        assert!(instance.flags() == vk::GeometryInstanceFlagsKHR::FORCE_OPAQUE);
+        assert_eq!(instance.acceleration_structure_address(), 0x1000);
+
+        instance.set_mask(0xff);
+        instance.set_flags(vk::GeometryInstanceFlagsKHR::TRIANGLE_FACING_CULL_DISABLE);
+        assert_eq!(instance.instance_custom_index(), 0x00ab_cdef);
+        assert_eq!(instance.shader_binding_table_record_offset(), 7);
+        assert_eq!(instance.transform.rows()[2], [0.0, 0.0, 1.0, 3.0]);
+
+        let mut bytes = [0u8; 2 * INSTANCE_SIZE];
+        assert_eq!(write_instances(&mut bytes, &[instance, instance]), 128);
+        assert_eq!(bytes[48..52], 0xffab_cdefu32.to_ne_bytes());
+        assert_eq!(bytes[56..64], 0x1000u64.to_ne_bytes());
+    }
+
+    #[test]
+    #[should_panic]
+    fn custom_index_out_of_range() {
+        vk::AccelerationStructureInstanceKHR::new(
+            vk::TransformMatrixKHR::IDENTITY,
+            1 << 24,
+            0xff,
+            0,
+            vk::GeometryInstanceFlagsKHR::empty(),
+            0,
+        );
+    }
+
+    #[test]
+    fn transform_from_cols() {
+        let cols = [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [4.0, 5.0, 6.0, 1.0],
+        ];
+        assert_eq!(
+            vk::TransformMatrixKHR::from_cols(cols).matrix,
+            vk::TransformMatrixKHR::from_translation([4.0, 5.0, 6.0]).matrix
+        );
+    }
+}
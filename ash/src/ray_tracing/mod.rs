@@ -0,0 +1,27 @@
+//! Helpers for `VK_KHR_ray_tracing_pipeline` and `VK_KHR_acceleration_structure`.
+//!
+//! [`AccelerationStructureBuilder`] sizes, allocates and records batches of bottom- and
+//! top-level acceleration structure builds, including an optional compaction pass.
+//!
+//! [`write_instances()`] serializes [`vk::AccelerationStructureInstanceKHR`]s, which are
+//! constructed through [`vk::AccelerationStructureInstanceKHR::new()`] and its range-checked
+//! accessors, into the instance array read by top-level builds.
+//!
+//! [`ShaderBindingTableBuilder`] packs shader group handles and inline record data into the
+//! regions passed to [`RayTracingPipeline::cmd_trace_rays()`].
+//!
+//! [`vk::AccelerationStructureInstanceKHR`]: crate::vk::AccelerationStructureInstanceKHR
+//! [`vk::AccelerationStructureInstanceKHR::new()`]: crate::vk::AccelerationStructureInstanceKHR::new()
+//! [`RayTracingPipeline::cmd_trace_rays()`]: crate::extensions::khr::RayTracingPipeline::cmd_trace_rays()
+
+pub use self::acceleration_structure::{
+    AccelerationStructureBuild, AccelerationStructureBuilder, BuiltAccelerationStructure, Geometry,
+};
+pub use self::instance::{
+    write_instances, write_instances_to_allocation, INSTANCE_ALIGNMENT, INSTANCE_SIZE,
+};
+pub use self::shader_binding_table::{ShaderBindingTable, ShaderBindingTableBuilder};
+
+mod acceleration_structure;
+mod instance;
+mod shader_binding_table;
@@ -0,0 +1,341 @@
+use crate::extensions::khr::RayTracingPipeline;
+use crate::prelude::*;
+use crate::vk;
+
+fn align_up(value: u64, alignment: u64) -> u64 {
+    value + (alignment - value % alignment) % alignment
+}
+
+/// A shader group handle followed by the inline data of its shader record.
+#[derive(Clone, Copy, Debug)]
+struct ShaderRecord<'a> {
+    group_index: u32,
+    data: &'a [u8],
+}
+
+/// Placement of a region within [`ShaderBindingTable::data()`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Region {
+    offset: u64,
+    stride: u64,
+    size: u64,
+}
+
+impl Region {
+    fn device_region(
+        &self,
+        device_address: vk::DeviceAddress,
+    ) -> vk::StridedDeviceAddressRegionKHR {
+        if self.size == 0 {
+            return vk::StridedDeviceAddressRegionKHR::default();
+        }
+        vk::StridedDeviceAddressRegionKHR {
+            device_address: device_address + self.offset,
+            stride: self.stride,
+            size: self.size,
+        }
+    }
+}
+
+/// Lays out the ray generation, miss, hit and callable regions of a shader binding table.
+///
+/// Records are added with the index of their shader group in the pipeline and optional inline
+/// data that follows the shader group handle. Every record in a region is padded to the same
+/// stride, a multiple of `shader_group_handle_alignment`, and every region starts at a multiple
+/// of `shader_group_base_alignment`. Every ray generation record is placed at its own base
+/// alignment so that any of them can be passed to [`RayTracingPipeline::cmd_trace_rays()`].
+///
+/// ```no_run
+/// # use ash::{extensions::khr::RayTracingPipeline, ray_tracing::ShaderBindingTableBuilder, vk};
+/// # unsafe fn example(
+/// #     ray_tracing: &RayTracingPipeline,
+/// #     properties: &vk::PhysicalDeviceRayTracingPipelinePropertiesKHR,
+/// #     pipeline: vk::Pipeline,
+/// #     command_buffer: vk::CommandBuffer,
+/// #     buffer_address: vk::DeviceAddress,
+/// # ) -> ash::prelude::VkResult<()> {
+/// let material = [0u8; 16];
+/// let sbt = ShaderBindingTableBuilder::new(properties)
+///     .raygen(0, &[])
+///     .miss(1, &[])
+///     .hit(2, &material)
+///     .build_from_pipeline(ray_tracing, pipeline, 3)?;
+/// // Upload `sbt.data()` to a buffer at `buffer_address`, aligned to `sbt.base_alignment()`.
+/// let [raygen, miss, hit, callable] = sbt.regions(buffer_address);
+/// ray_tracing.cmd_trace_rays(command_buffer, &raygen, &miss, &hit, &callable, 1920, 1080, 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ShaderBindingTableBuilder<'a> {
+    handle_size: u64,
+    handle_alignment: u64,
+    base_alignment: u64,
+    max_stride: u64,
+    raygen: Vec<ShaderRecord<'a>>,
+    miss: Vec<ShaderRecord<'a>>,
+    hit: Vec<ShaderRecord<'a>>,
+    callable: Vec<ShaderRecord<'a>>,
+}
+
+impl<'a> ShaderBindingTableBuilder<'a> {
+    /// Takes the handle size and alignments from `properties`, as returned by
+    /// [`RayTracingPipeline::get_properties()`].
+    pub fn new(properties: &vk::PhysicalDeviceRayTracingPipelinePropertiesKHR) -> Self {
+        Self {
+            handle_size: u64::from(properties.shader_group_handle_size),
+            handle_alignment: u64::from(properties.shader_group_handle_alignment.max(1)),
+            base_alignment: u64::from(properties.shader_group_base_alignment.max(1)),
+            max_stride: u64::from(properties.max_shader_group_stride),
+            raygen: Vec::new(),
+            miss: Vec::new(),
+            hit: Vec::new(),
+            callable: Vec::new(),
+        }
+    }
+
+    /// Adds a ray generation record for the shader group at `group_index` followed by `data`,
+    /// which may be empty.
+    pub fn raygen(mut self, group_index: u32, data: &'a [u8]) -> Self {
+        self.raygen.push(ShaderRecord { group_index, data });
+        self
+    }
+
+    /// Adds a miss record for the shader group at `group_index` followed by `data`, which may be
+    /// empty.
+    pub fn miss(mut self, group_index: u32, data: &'a [u8]) -> Self {
+        self.miss.push(ShaderRecord { group_index, data });
+        self
+    }
+
+    /// Adds a hit record for the hit group at `group_index` followed by `data`, which may be
+    /// empty.
+    pub fn hit(mut self, group_index: u32, data: &'a [u8]) -> Self {
+        self.hit.push(ShaderRecord { group_index, data });
+        self
+    }
+
+    /// Adds a callable record for the shader group at `group_index` followed by `data`, which
+    /// may be empty.
+    pub fn callable(mut self, group_index: u32, data: &'a [u8]) -> Self {
+        self.callable.push(ShaderRecord { group_index, data });
+        self
+    }
+
+    /// Alignment the device address of the shader binding table buffer must have.
+    pub fn base_alignment(&self) -> u64 {
+        self.base_alignment
+    }
+
+    fn record_stride(&self, records: &[ShaderRecord<'_>], alignment: u64) -> u64 {
+        let max_data_size = records
+            .iter()
+            .map(|record| record.data.len() as u64)
+            .max()
+            .unwrap_or(0);
+        let stride = align_up(self.handle_size + max_data_size, alignment);
+        assert!(
+            stride <= self.max_stride,
+            "shader record stride {} exceeds max_shader_group_stride {}",
+            stride,
+            self.max_stride
+        );
+        stride
+    }
+
+    /// Packs the records using `handles`, the shader group handles of the pipeline as returned by
+    /// [`RayTracingPipeline::get_ray_tracing_shader_group_handles()`] starting at group `0`.
+    pub fn build(&self, handles: &[u8]) -> ShaderBindingTable {
+        let mut data = Vec::new();
+        let mut write_region = |records: &[ShaderRecord<'_>], stride: u64| {
+            let offset = align_up(data.len() as u64, self.base_alignment);
+            let size = stride * records.len() as u64;
+            data.resize((offset + size) as usize, 0);
+            for (i, record) in records.iter().enumerate() {
+                let handle_start = (u64::from(record.group_index) * self.handle_size) as usize;
+                let handle = &handles[handle_start..handle_start + self.handle_size as usize];
+                let record_start = (offset + i as u64 * stride) as usize;
+                let (record_handle, record_data) =
+                    data[record_start..].split_at_mut(self.handle_size as usize);
+                record_handle.copy_from_slice(handle);
+                record_data[..record.data.len()].copy_from_slice(record.data);
+            }
+            Region {
+                offset,
+                stride,
+                size,
+            }
+        };
+
+        let raygen_stride = self.record_stride(&self.raygen, self.base_alignment);
+        let raygen = write_region(&self.raygen, raygen_stride);
+        let miss = write_region(
+            &self.miss,
+            self.record_stride(&self.miss, self.handle_alignment),
+        );
+        let hit = write_region(
+            &self.hit,
+            self.record_stride(&self.hit, self.handle_alignment),
+        );
+        let callable = write_region(
+            &self.callable,
+            self.record_stride(&self.callable, self.handle_alignment),
+        );
+
+        ShaderBindingTable {
+            data,
+            base_alignment: self.base_alignment,
+            raygen,
+            miss,
+            hit,
+            callable,
+        }
+    }
+
+    /// Queries the handles of the first `group_count` shader groups of `pipeline` and packs the
+    /// records with [`Self::build()`].
+    pub unsafe fn build_from_pipeline(
+        &self,
+        ray_tracing: &RayTracingPipeline,
+        pipeline: vk::Pipeline,
+        group_count: u32,
+    ) -> VkResult<ShaderBindingTable> {
+        let handles = ray_tracing.get_ray_tracing_shader_group_handles(
+            pipeline,
+            0,
+            group_count,
+            group_count as usize * self.handle_size as usize,
+        )?;
+        Ok(self.build(&handles))
+    }
+}
+
+/// The packed contents of a shader binding table, built by [`ShaderBindingTableBuilder`].
+#[derive(Clone, Debug)]
+pub struct ShaderBindingTable {
+    data: Vec<u8>,
+    base_alignment: u64,
+    raygen: Region,
+    miss: Region,
+    hit: Region,
+    callable: Region,
+}
+
+impl ShaderBindingTable {
+    /// The bytes to upload to the shader binding table buffer.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Alignment the device address of the shader binding table buffer must have.
+    pub fn base_alignment(&self) -> u64 {
+        self.base_alignment
+    }
+
+    /// Number of ray generation records.
+    pub fn raygen_count(&self) -> usize {
+        match self.raygen.stride {
+            0 => 0,
+            stride => (self.raygen.size / stride) as usize,
+        }
+    }
+
+    /// Returns the region of the ray generation record at `index` for a table uploaded at
+    /// `device_address`.
+    pub fn raygen_region(
+        &self,
+        device_address: vk::DeviceAddress,
+        index: usize,
+    ) -> vk::StridedDeviceAddressRegionKHR {
+        assert!(index < self.raygen_count());
+        let stride = self.raygen.stride;
+        Region {
+            offset: self.raygen.offset + index as u64 * stride,
+            stride,
+            size: stride,
+        }
+        .device_region(device_address)
+    }
+
+    /// Returns the ray generation, miss, hit and callable regions for a table uploaded at
+    /// `device_address`, using the first ray generation record. Regions without records are
+    /// empty.
+    pub fn regions(
+        &self,
+        device_address: vk::DeviceAddress,
+    ) -> [vk::StridedDeviceAddressRegionKHR; 4] {
+        assert_eq!(device_address % self.base_alignment, 0);
+        let raygen = if self.raygen_count() > 0 {
+            self.raygen_region(device_address, 0)
+        } else {
+            vk::StridedDeviceAddressRegionKHR::default()
+        };
+        [
+            raygen,
+            self.miss.device_region(device_address),
+            self.hit.device_region(device_address),
+            self.callable.device_region(device_address),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack_regions() {
+        let properties = vk::PhysicalDeviceRayTracingPipelinePropertiesKHR {
+            shader_group_handle_size: 32,
+            shader_group_handle_alignment: 32,
+            shader_group_base_alignment: 64,
+            max_shader_group_stride: 4096,
+            ..Default::default()
+        };
+        let handles = (0..4u8)
+            .flat_map(|group| vec![group + 1; 32])
+            .collect::<Vec<_>>();
+        let hit_data = [0xffu8; 8];
+        let sbt = ShaderBindingTableBuilder::new(&properties)
+            .raygen(0, &[])
+            .miss(1, &[])
+            .miss(2, &[])
+            .hit(3, &hit_data)
+            .build(&handles);
+
+        assert_eq!(
+            sbt.raygen,
+            Region {
+                offset: 0,
+                stride: 64,
+                size: 64
+            }
+        );
+        assert_eq!(
+            sbt.miss,
+            Region {
+                offset: 64,
+                stride: 32,
+                size: 64
+            }
+        );
+        assert_eq!(
+            sbt.hit,
+            Region {
+                offset: 128,
+                stride: 64,
+                size: 64
+            }
+        );
+        assert_eq!(sbt.callable.size, 0);
+        assert_eq!(sbt.data().len(), 192);
+        assert!(sbt.data()[96..128].iter().all(|&b| b == 3));
+        assert!(sbt.data()[128..160].iter().all(|&b| b == 4));
+        assert_eq!(sbt.data()[160..168], hit_data);
+
+        let [raygen, miss, _, callable] = sbt.regions(0x1000);
+        assert_eq!(raygen.size, raygen.stride);
+        assert_eq!(miss.device_address, 0x1040);
+        assert_eq!(callable.device_address, 0);
+    }
+}
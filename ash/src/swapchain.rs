@@ -0,0 +1,617 @@
+//! Swapchain creation, recreation and frame pacing on top of [`khr::Swapchain`].
+//!
+//! [`SwapchainManager`] negotiates the surface format and present mode with [`khr::Surface`],
+//! owns the swapchain images and their views, and cycles through a fixed number of frames in
+//! flight, each with its own acquire semaphore and fence. The swapchain is recreated (passing the
+//! previous one as `old_swapchain`) whenever presentation reports it out of date or suboptimal,
+//! or when the requested extent changes.
+
+use crate::extensions::khr;
+use crate::prelude::*;
+use crate::vk;
+use crate::{Device, Entry, Instance};
+
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct SwapchainCreateDesc {
+    pub physical_device: vk::PhysicalDevice,
+    pub surface: vk::SurfaceKHR,
+    /// Surface formats in order of preference. The first format reported by the surface is used
+    /// when none of them are supported.
+    pub preferred_formats: Vec<vk::SurfaceFormatKHR>,
+    /// Present modes in order of preference, falling back to [`vk::PresentModeKHR::FIFO`].
+    pub preferred_present_modes: Vec<vk::PresentModeKHR>,
+    pub image_usage: vk::ImageUsageFlags,
+    /// Requested number of swapchain images, clamped to the surface capabilities.
+    pub min_image_count: u32,
+    /// Number of frames the host may record ahead of the device.
+    pub frames_in_flight: usize,
+    /// Attach a present id to every present and have [`SwapchainManager::acquire_frame()`] wait
+    /// until the previous present of the same frame slot has completed. Requires the
+    /// `VK_KHR_present_id` and `VK_KHR_present_wait` extensions and features.
+    pub present_wait: bool,
+}
+
+impl Default for SwapchainCreateDesc {
+    fn default() -> Self {
+        Self {
+            physical_device: vk::PhysicalDevice::null(),
+            surface: vk::SurfaceKHR::null(),
+            preferred_formats: vec![
+                vk::SurfaceFormatKHR {
+                    format: vk::Format::B8G8R8A8_SRGB,
+                    color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
+                },
+                vk::SurfaceFormatKHR {
+                    format: vk::Format::R8G8B8A8_SRGB,
+                    color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
+                },
+            ],
+            preferred_present_modes: vec![vk::PresentModeKHR::MAILBOX],
+            image_usage: vk::ImageUsageFlags::COLOR_ATTACHMENT,
+            min_image_count: 3,
+            frames_in_flight: 2,
+            present_wait: false,
+        }
+    }
+}
+
+/// A swapchain image acquired by [`SwapchainManager::acquire_frame()`].
+///
+/// The work rendering to [`Frame::image`] must wait on [`Frame::image_available`], signal
+/// [`Frame::render_finished`] and signal [`Frame::fence`] with its last submission before the
+/// frame is handed to [`SwapchainManager::present()`].
+#[derive(Clone, Copy, Debug)]
+pub struct Frame {
+    /// Index of the frame in flight, in `0..frames_in_flight`.
+    pub frame_index: usize,
+    pub image_index: u32,
+    pub image: vk::Image,
+    pub image_view: vk::ImageView,
+    pub image_available: vk::Semaphore,
+    pub render_finished: vk::Semaphore,
+    pub fence: vk::Fence,
+    /// Present id attached to the present of this frame, or `0` without
+    /// [`SwapchainCreateDesc::present_wait`].
+    pub present_id: u64,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct FrameSync {
+    image_available: vk::Semaphore,
+    fence: vk::Fence,
+    /// Present id of the last successful present from this frame slot.
+    present_id: u64,
+}
+
+/// Owns a swapchain together with its image views and per-frame synchronization objects.
+///
+/// The manager does not implement [`Drop`]; call [`SwapchainManager::destroy()`] once it is no
+/// longer needed. The surface itself remains owned by the caller.
+pub struct SwapchainManager {
+    device: Device,
+    surface_loader: khr::Surface,
+    swapchain_loader: khr::Swapchain,
+    present_wait: Option<khr::PresentWait>,
+    desc: SwapchainCreateDesc,
+
+    swapchain: vk::SwapchainKHR,
+    format: vk::SurfaceFormatKHR,
+    present_mode: vk::PresentModeKHR,
+    extent: vk::Extent2D,
+    requested_extent: vk::Extent2D,
+    images: Vec<vk::Image>,
+    image_views: Vec<vk::ImageView>,
+    /// One per swapchain image, as a present may still be waiting on it when the frame slot that
+    /// signaled it comes around again.
+    render_finished: Vec<vk::Semaphore>,
+    /// Fence of the frame that last rendered to every image.
+    images_in_flight: Vec<vk::Fence>,
+    needs_recreate: bool,
+
+    frames: Vec<FrameSync>,
+    frame_index: usize,
+    next_present_id: u64,
+}
+
+unsafe impl Send for SwapchainManager {}
+
+impl SwapchainManager {
+    /// Creates the swapchain with `extent`, which is only used when the surface does not
+    /// dictate the extent itself.
+    pub unsafe fn new(
+        entry: &Entry,
+        instance: &Instance,
+        device: &Device,
+        desc: SwapchainCreateDesc,
+        extent: vk::Extent2D,
+    ) -> VkResult<Self> {
+        assert!(desc.frames_in_flight > 0);
+        let present_wait = if desc.present_wait {
+            Some(khr::PresentWait::new(instance, device))
+        } else {
+            None
+        };
+
+        let mut manager = Self {
+            device: device.clone(),
+            surface_loader: khr::Surface::new(entry, instance),
+            swapchain_loader: khr::Swapchain::new(instance, device),
+            present_wait,
+            desc,
+            swapchain: vk::SwapchainKHR::null(),
+            format: vk::SurfaceFormatKHR::default(),
+            present_mode: vk::PresentModeKHR::FIFO,
+            extent: vk::Extent2D::default(),
+            requested_extent: extent,
+            images: Vec::new(),
+            image_views: Vec::new(),
+            render_finished: Vec::new(),
+            images_in_flight: Vec::new(),
+            needs_recreate: false,
+            frames: Vec::new(),
+            frame_index: 0,
+            next_present_id: 1,
+        };
+
+        let semaphore_info = vk::SemaphoreCreateInfo::default();
+        let fence_info = vk::FenceCreateInfo::builder().flags(vk::FenceCreateFlags::SIGNALED);
+        for _ in 0..manager.desc.frames_in_flight {
+            let image_available = match device.create_semaphore(&semaphore_info, None) {
+                Ok(semaphore) => semaphore,
+                Err(err) => {
+                    manager.destroy()?;
+                    return Err(err);
+                }
+            };
+            let fence = match device.create_fence(&fence_info, None) {
+                Ok(fence) => fence,
+                Err(err) => {
+                    device.destroy_semaphore(image_available, None);
+                    manager.destroy()?;
+                    return Err(err);
+                }
+            };
+            manager.frames.push(FrameSync {
+                image_available,
+                fence,
+                present_id: 0,
+            });
+        }
+
+        if let Err(err) = manager.recreate(extent) {
+            manager.destroy()?;
+            return Err(err);
+        }
+        Ok(manager)
+    }
+
+    pub fn swapchain(&self) -> vk::SwapchainKHR {
+        self.swapchain
+    }
+
+    pub fn format(&self) -> vk::SurfaceFormatKHR {
+        self.format
+    }
+
+    pub fn present_mode(&self) -> vk::PresentModeKHR {
+        self.present_mode
+    }
+
+    pub fn extent(&self) -> vk::Extent2D {
+        self.extent
+    }
+
+    pub fn images(&self) -> &[vk::Image] {
+        &self.images
+    }
+
+    pub fn image_views(&self) -> &[vk::ImageView] {
+        &self.image_views
+    }
+
+    pub fn frames_in_flight(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Forces the swapchain to be recreated by the next [`SwapchainManager::acquire_frame()`].
+    pub fn invalidate(&mut self) {
+        self.needs_recreate = true;
+    }
+
+    /// Recreates the swapchain, retiring the current one.
+    ///
+    /// Returns [`vk::Result::ERROR_OUT_OF_DATE_KHR`] without touching the current swapchain when
+    /// the surface has a zero-sized extent, as happens while a window is minimized.
+    pub unsafe fn recreate(&mut self, extent: vk::Extent2D) -> VkResult<()> {
+        let physical_device = self.desc.physical_device;
+        let surface = self.desc.surface;
+        let capabilities = self
+            .surface_loader
+            .get_physical_device_surface_capabilities(physical_device, surface)?;
+        let formats = self
+            .surface_loader
+            .get_physical_device_surface_formats(physical_device, surface)?;
+        let present_modes = self
+            .surface_loader
+            .get_physical_device_surface_present_modes(physical_device, surface)?;
+
+        let image_extent = choose_extent(&capabilities, extent);
+        if image_extent.width == 0 || image_extent.height == 0 {
+            return Err(vk::Result::ERROR_OUT_OF_DATE_KHR);
+        }
+        let format = choose_surface_format(&formats, &self.desc.preferred_formats);
+        let present_mode = choose_present_mode(&present_modes, &self.desc.preferred_present_modes);
+        let mut min_image_count = self.desc.min_image_count.max(capabilities.min_image_count);
+        if capabilities.max_image_count > 0 {
+            min_image_count = min_image_count.min(capabilities.max_image_count);
+        }
+        let pre_transform = if capabilities
+            .supported_transforms
+            .contains(vk::SurfaceTransformFlagsKHR::IDENTITY)
+        {
+            vk::SurfaceTransformFlagsKHR::IDENTITY
+        } else {
+            capabilities.current_transform
+        };
+        let composite_alpha = [
+            vk::CompositeAlphaFlagsKHR::OPAQUE,
+            vk::CompositeAlphaFlagsKHR::PRE_MULTIPLIED,
+            vk::CompositeAlphaFlagsKHR::POST_MULTIPLIED,
+            vk::CompositeAlphaFlagsKHR::INHERIT,
+        ]
+        .iter()
+        .copied()
+        .find(|&alpha| capabilities.supported_composite_alpha.contains(alpha))
+        .unwrap_or(vk::CompositeAlphaFlagsKHR::OPAQUE);
+
+        let old_swapchain = self.swapchain;
+        let create_info = vk::SwapchainCreateInfoKHR::builder()
+            .surface(surface)
+            .min_image_count(min_image_count)
+            .image_format(format.format)
+            .image_color_space(format.color_space)
+            .image_extent(image_extent)
+            .image_array_layers(1)
+            .image_usage(self.desc.image_usage)
+            .image_sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .pre_transform(pre_transform)
+            .composite_alpha(composite_alpha)
+            .present_mode(present_mode)
+            .clipped(true)
+            .old_swapchain(old_swapchain);
+        let swapchain = self.swapchain_loader.create_swapchain(&create_info, None)?;
+
+        // The retired swapchain may still be in use by pending presents and rendering.
+        self.device.device_wait_idle()?;
+        self.destroy_swapchain_resources();
+        // Ids presented to the retired swapchain never complete on the new one.
+        forget_present_ids(&mut self.frames);
+        self.swapchain = swapchain;
+        self.format = format;
+        self.present_mode = present_mode;
+        self.extent = image_extent;
+        self.requested_extent = extent;
+        self.needs_recreate = false;
+
+        if let Err(err) = self.create_image_resources() {
+            // Keep the new swapchain so that it is retired by the next recreation.
+            self.destroy_image_resources();
+            self.needs_recreate = true;
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    unsafe fn create_image_resources(&mut self) -> VkResult<()> {
+        self.images = self.swapchain_loader.get_swapchain_images(self.swapchain)?;
+        for &image in &self.images {
+            let view_info = vk::ImageViewCreateInfo::builder()
+                .image(image)
+                .view_type(vk::ImageViewType::TYPE_2D)
+                .format(self.format.format)
+                .subresource_range(vk::ImageSubresourceRange {
+                    aspect_mask: vk::ImageAspectFlags::COLOR,
+                    base_mip_level: 0,
+                    level_count: 1,
+                    base_array_layer: 0,
+                    layer_count: 1,
+                });
+            self.image_views
+                .push(self.device.create_image_view(&view_info, None)?);
+        }
+        let semaphore_info = vk::SemaphoreCreateInfo::default();
+        for _ in 0..self.images.len() {
+            self.render_finished
+                .push(self.device.create_semaphore(&semaphore_info, None)?);
+        }
+        self.images_in_flight = vec![vk::Fence::null(); self.images.len()];
+        Ok(())
+    }
+
+    /// Waits for the next frame slot to become free and acquires a swapchain image for it,
+    /// recreating the swapchain first if it is out of date or `extent` has changed.
+    ///
+    /// Returns [`vk::Result::ERROR_OUT_OF_DATE_KHR`] when the surface is currently zero-sized;
+    /// skip the frame and try again later.
+    pub unsafe fn acquire_frame(&mut self, extent: vk::Extent2D, timeout: u64) -> VkResult<Frame> {
+        if self.needs_recreate || extent != self.requested_extent {
+            self.recreate(extent)?;
+        }
+
+        let frame_index = self.frame_index;
+        let sync = self.frames[frame_index];
+        self.device.wait_for_fences(&[sync.fence], true, timeout)?;
+        if let Some(present_wait) = &self.present_wait {
+            if sync.present_id != 0 {
+                match present_wait.wait_for_present(self.swapchain, sync.present_id, timeout) {
+                    Ok(()) | Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => {}
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+
+        let mut recreated = false;
+        let image_index = loop {
+            match self.swapchain_loader.acquire_next_image(
+                self.swapchain,
+                timeout,
+                sync.image_available,
+                vk::Fence::null(),
+            ) {
+                Ok((image_index, suboptimal)) => {
+                    // Keep rendering to a suboptimal swapchain, it is replaced on the next frame.
+                    self.needs_recreate |= suboptimal;
+                    break image_index;
+                }
+                Err(vk::Result::ERROR_OUT_OF_DATE_KHR) if !recreated => {
+                    self.recreate(extent)?;
+                    recreated = true;
+                }
+                Err(err) => return Err(err),
+            }
+        };
+
+        let image_fence = self.images_in_flight[image_index as usize];
+        if image_fence != vk::Fence::null() && image_fence != sync.fence {
+            self.device.wait_for_fences(&[image_fence], true, timeout)?;
+        }
+        self.images_in_flight[image_index as usize] = sync.fence;
+        self.device.reset_fences(&[sync.fence])?;
+
+        let present_id = if self.present_wait.is_some() {
+            self.next_present_id += 1;
+            self.next_present_id - 1
+        } else {
+            0
+        };
+        self.frame_index = (frame_index + 1) % self.frames.len();
+        Ok(Frame {
+            frame_index,
+            image_index,
+            image: self.images[image_index as usize],
+            image_view: self.image_views[image_index as usize],
+            image_available: sync.image_available,
+            render_finished: self.render_finished[image_index as usize],
+            fence: sync.fence,
+            present_id,
+        })
+    }
+
+    /// Presents `frame` once [`Frame::render_finished`] is signaled.
+    ///
+    /// An out of date or suboptimal swapchain is not an error; it is recreated by the next
+    /// [`SwapchainManager::acquire_frame()`].
+    pub unsafe fn present(&mut self, queue: vk::Queue, frame: &Frame) -> VkResult<()> {
+        let wait_semaphores = [frame.render_finished];
+        let swapchains = [self.swapchain];
+        let image_indices = [frame.image_index];
+        let present_ids = [frame.present_id];
+        let mut present_id_info = vk::PresentIdKHR::builder().present_ids(&present_ids);
+        let mut present_info = vk::PresentInfoKHR::builder()
+            .wait_semaphores(&wait_semaphores)
+            .swapchains(&swapchains)
+            .image_indices(&image_indices);
+        if self.present_wait.is_some() {
+            present_info = present_info.push_next(&mut present_id_info);
+        }
+
+        match self.swapchain_loader.queue_present(queue, &present_info) {
+            Ok(suboptimal) => {
+                self.needs_recreate |= suboptimal;
+                self.frames[frame.frame_index].present_id = frame.present_id;
+                Ok(())
+            }
+            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => {
+                self.needs_recreate = true;
+                Ok(())
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Waits until the present with `present_id` has been displayed.
+    ///
+    /// Returns [`vk::Result::ERROR_FEATURE_NOT_PRESENT`] when the manager was not created with
+    /// [`SwapchainCreateDesc::present_wait`].
+    pub unsafe fn wait_for_present(&self, present_id: u64, timeout: u64) -> VkResult<()> {
+        match &self.present_wait {
+            Some(present_wait) => {
+                present_wait.wait_for_present(self.swapchain, present_id, timeout)
+            }
+            None => Err(vk::Result::ERROR_FEATURE_NOT_PRESENT),
+        }
+    }
+
+    unsafe fn destroy_image_resources(&mut self) {
+        for view in self.image_views.drain(..) {
+            self.device.destroy_image_view(view, None);
+        }
+        for semaphore in self.render_finished.drain(..) {
+            self.device.destroy_semaphore(semaphore, None);
+        }
+        self.images.clear();
+        self.images_in_flight.clear();
+    }
+
+    unsafe fn destroy_swapchain_resources(&mut self) {
+        self.destroy_image_resources();
+        if self.swapchain != vk::SwapchainKHR::null() {
+            self.swapchain_loader
+                .destroy_swapchain(self.swapchain, None);
+            self.swapchain = vk::SwapchainKHR::null();
+        }
+    }
+
+    /// Waits for the device to become idle and destroys the swapchain and all synchronization
+    /// objects.
+    pub unsafe fn destroy(mut self) -> VkResult<()> {
+        self.device.device_wait_idle()?;
+        self.destroy_swapchain_resources();
+        for frame in self.frames.drain(..) {
+            self.device.destroy_semaphore(frame.image_available, None);
+            self.device.destroy_fence(frame.fence, None);
+        }
+        Ok(())
+    }
+}
+
+/// Clears the ids of presents to a retired swapchain, so that acquiring does not wait for them.
+fn forget_present_ids(frames: &mut [FrameSync]) {
+    for frame in frames {
+        frame.present_id = 0;
+    }
+}
+
+fn choose_surface_format(
+    available: &[vk::SurfaceFormatKHR],
+    preferred: &[vk::SurfaceFormatKHR],
+) -> vk::SurfaceFormatKHR {
+    // A single UNDEFINED format means that the surface has no preference.
+    if let [only] = available {
+        if only.format == vk::Format::UNDEFINED {
+            return preferred.first().copied().unwrap_or(vk::SurfaceFormatKHR {
+                format: vk::Format::B8G8R8A8_UNORM,
+                color_space: only.color_space,
+            });
+        }
+    }
+    preferred
+        .iter()
+        .find(|format| available.contains(format))
+        .or_else(|| available.first())
+        .copied()
+        .unwrap_or_default()
+}
+
+fn choose_present_mode(
+    available: &[vk::PresentModeKHR],
+    preferred: &[vk::PresentModeKHR],
+) -> vk::PresentModeKHR {
+    preferred
+        .iter()
+        .copied()
+        .find(|mode| available.contains(mode))
+        .unwrap_or(vk::PresentModeKHR::FIFO)
+}
+
+fn choose_extent(
+    capabilities: &vk::SurfaceCapabilitiesKHR,
+    requested: vk::Extent2D,
+) -> vk::Extent2D {
+    if capabilities.current_extent.width != u32::MAX {
+        return capabilities.current_extent;
+    }
+    vk::Extent2D {
+        width: requested.width.clamp(
+            capabilities.min_image_extent.width,
+            capabilities.max_image_extent.width,
+        ),
+        height: requested.height.clamp(
+            capabilities.min_image_extent.height,
+            capabilities.max_image_extent.height,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn surface_negotiation() {
+        let srgb = vk::SurfaceFormatKHR {
+            format: vk::Format::B8G8R8A8_SRGB,
+            color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
+        };
+        let unorm = vk::SurfaceFormatKHR {
+            format: vk::Format::B8G8R8A8_UNORM,
+            color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
+        };
+        assert!(choose_surface_format(&[unorm, srgb], &[srgb]) == srgb);
+        assert!(choose_surface_format(&[unorm], &[srgb]) == unorm);
+        let undefined = vk::SurfaceFormatKHR {
+            format: vk::Format::UNDEFINED,
+            color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
+        };
+        assert!(choose_surface_format(&[undefined], &[srgb]) == srgb);
+
+        let modes = [vk::PresentModeKHR::FIFO, vk::PresentModeKHR::IMMEDIATE];
+        assert!(
+            choose_present_mode(&modes, &[vk::PresentModeKHR::MAILBOX]) == vk::PresentModeKHR::FIFO
+        );
+        assert!(
+            choose_present_mode(
+                &modes,
+                &[vk::PresentModeKHR::MAILBOX, vk::PresentModeKHR::IMMEDIATE]
+            ) == vk::PresentModeKHR::IMMEDIATE
+        );
+    }
+
+    #[test]
+    fn recreation_forgets_present_ids() {
+        let mut frames = [FrameSync {
+            image_available: vk::Semaphore::null(),
+            fence: vk::Fence::null(),
+            present_id: 7,
+        }; 2];
+        frames[1].present_id = 8;
+        forget_present_ids(&mut frames);
+        assert!(frames.iter().all(|frame| frame.present_id == 0));
+    }
+
+    #[test]
+    fn extent_is_clamped() {
+        let mut capabilities = vk::SurfaceCapabilitiesKHR {
+            current_extent: vk::Extent2D {
+                width: u32::MAX,
+                height: u32::MAX,
+            },
+            min_image_extent: vk::Extent2D {
+                width: 1,
+                height: 1,
+            },
+            max_image_extent: vk::Extent2D {
+                width: 4096,
+                height: 4096,
+            },
+            ..Default::default()
+        };
+        let requested = vk::Extent2D {
+            width: 8192,
+            height: 600,
+        };
+        assert!(
+            choose_extent(&capabilities, requested)
+                == vk::Extent2D {
+                    width: 4096,
+                    height: 600
+                }
+        );
+        capabilities.current_extent = vk::Extent2D {
+            width: 800,
+            height: 600,
+        };
+        assert!(choose_extent(&capabilities, requested) == capabilities.current_extent);
+    }
+}
@@ -0,0 +1,353 @@
+use crate::vk;
+
+/// A single way in which a resource is accessed, modeled on
+/// [`simple_vulkan_synchronization`](https://github.com/Tobski/simple_vulkan_synchronization).
+///
+/// Variants ending in `Write` (plus [`AccessType::ColorAttachmentReadWrite`],
+/// [`AccessType::HostPreinitialized`] and [`AccessType::General`]) modify the resource; all
+/// other variants only read from it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AccessType {
+    /// No access. Useful as the initial state of a resource, or to discard its contents.
+    Nothing,
+
+    /// Read as an indirect buffer for drawing or dispatch.
+    IndirectBuffer,
+    /// Read as an index buffer for drawing.
+    IndexBuffer,
+    /// Read as a vertex buffer for drawing.
+    VertexBuffer,
+    /// Read as a uniform buffer in a vertex shader.
+    VertexShaderReadUniformBuffer,
+    /// Read as a sampled image or uniform texel buffer in a vertex shader.
+    VertexShaderReadSampledImageOrUniformTexelBuffer,
+    /// Read as any other resource in a vertex shader.
+    VertexShaderReadOther,
+    /// Read as a uniform buffer in a tessellation control shader.
+    TessellationControlShaderReadUniformBuffer,
+    /// Read as a sampled image or uniform texel buffer in a tessellation control shader.
+    TessellationControlShaderReadSampledImageOrUniformTexelBuffer,
+    /// Read as any other resource in a tessellation control shader.
+    TessellationControlShaderReadOther,
+    /// Read as a uniform buffer in a tessellation evaluation shader.
+    TessellationEvaluationShaderReadUniformBuffer,
+    /// Read as a sampled image or uniform texel buffer in a tessellation evaluation shader.
+    TessellationEvaluationShaderReadSampledImageOrUniformTexelBuffer,
+    /// Read as any other resource in a tessellation evaluation shader.
+    TessellationEvaluationShaderReadOther,
+    /// Read as a uniform buffer in a geometry shader.
+    GeometryShaderReadUniformBuffer,
+    /// Read as a sampled image or uniform texel buffer in a geometry shader.
+    GeometryShaderReadSampledImageOrUniformTexelBuffer,
+    /// Read as any other resource in a geometry shader.
+    GeometryShaderReadOther,
+    /// Read as a uniform buffer in a fragment shader.
+    FragmentShaderReadUniformBuffer,
+    /// Read as a sampled image or uniform texel buffer in a fragment shader.
+    FragmentShaderReadSampledImageOrUniformTexelBuffer,
+    /// Read as an input attachment with a color format in a fragment shader.
+    FragmentShaderReadColorInputAttachment,
+    /// Read as an input attachment with a depth/stencil format in a fragment shader.
+    FragmentShaderReadDepthStencilInputAttachment,
+    /// Read as any other resource in a fragment shader.
+    FragmentShaderReadOther,
+    /// Read by blending/logic operations or subpass load operations.
+    ColorAttachmentRead,
+    /// Read by depth/stencil tests or subpass load operations.
+    DepthStencilAttachmentRead,
+    /// Read as a uniform buffer in a compute shader.
+    ComputeShaderReadUniformBuffer,
+    /// Read as a sampled image or uniform texel buffer in a compute shader.
+    ComputeShaderReadSampledImageOrUniformTexelBuffer,
+    /// Read as any other resource in a compute shader.
+    ComputeShaderReadOther,
+    /// Read as a uniform buffer in any shader.
+    AnyShaderReadUniformBuffer,
+    /// Read as a uniform buffer in any shader, or as a vertex buffer.
+    AnyShaderReadUniformBufferOrVertexBuffer,
+    /// Read as a sampled image or uniform texel buffer in any shader.
+    AnyShaderReadSampledImageOrUniformTexelBuffer,
+    /// Read as any other resource (excluding attachments) in any shader.
+    AnyShaderReadOther,
+    /// Read as the source of a transfer operation.
+    TransferRead,
+    /// Read on the host.
+    HostRead,
+    /// Read by the presentation engine.
+    Present,
+
+    /// Written as any resource in a vertex shader.
+    VertexShaderWrite,
+    /// Written as any resource in a tessellation control shader.
+    TessellationControlShaderWrite,
+    /// Written as any resource in a tessellation evaluation shader.
+    TessellationEvaluationShaderWrite,
+    /// Written as any resource in a geometry shader.
+    GeometryShaderWrite,
+    /// Written as any resource in a fragment shader.
+    FragmentShaderWrite,
+    /// Written as a color attachment during rendering, or via a subpass store op.
+    ColorAttachmentWrite,
+    /// Written as a depth/stencil attachment during rendering, or via a subpass store op.
+    DepthStencilAttachmentWrite,
+    /// Written as a depth aspect of a depth/stencil attachment during rendering, whilst the
+    /// stencil aspect is read-only.
+    DepthAttachmentWriteStencilReadOnly,
+    /// Written as a stencil aspect of a depth/stencil attachment during rendering, whilst the
+    /// depth aspect is read-only.
+    StencilAttachmentWriteDepthReadOnly,
+    /// Written as any resource in a compute shader.
+    ComputeShaderWrite,
+    /// Written as any resource in any shader.
+    AnyShaderWrite,
+    /// Written as the destination of a transfer operation.
+    TransferWrite,
+    /// Data pre-filled by the host before device access starts.
+    HostPreinitialized,
+    /// Written on the host.
+    HostWrite,
+    /// Read or written as a color attachment during rendering.
+    ColorAttachmentReadWrite,
+    /// Covers any access. Useful for debugging, but generally inefficient.
+    General,
+}
+
+/// Pipeline stages, access flags and image layout required by an [`AccessType`].
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct AccessInfo {
+    pub stage_mask: vk::PipelineStageFlags,
+    pub access_mask: vk::AccessFlags,
+    pub image_layout: vk::ImageLayout,
+}
+
+impl AccessInfo {
+    /// [`Self::stage_mask`] as `VK_KHR_synchronization2` flags.
+    pub fn stage_mask2(&self) -> vk::PipelineStageFlags2KHR {
+        stage_flags2(self.stage_mask)
+    }
+
+    /// [`Self::access_mask`] as `VK_KHR_synchronization2` flags.
+    pub fn access_mask2(&self) -> vk::AccessFlags2KHR {
+        access_flags2(self.access_mask)
+    }
+}
+
+impl AccessType {
+    /// Returns the stages, access flags and layout this access requires.
+    pub fn info(self) -> AccessInfo {
+        use vk::AccessFlags as A;
+        use vk::ImageLayout as L;
+        use vk::PipelineStageFlags as S;
+
+        let (stage_mask, access_mask, image_layout) = match self {
+            Self::Nothing => (S::empty(), A::empty(), L::UNDEFINED),
+
+            Self::IndirectBuffer => (S::DRAW_INDIRECT, A::INDIRECT_COMMAND_READ, L::UNDEFINED),
+            Self::IndexBuffer => (S::VERTEX_INPUT, A::INDEX_READ, L::UNDEFINED),
+            Self::VertexBuffer => (S::VERTEX_INPUT, A::VERTEX_ATTRIBUTE_READ, L::UNDEFINED),
+            Self::VertexShaderReadUniformBuffer => {
+                (S::VERTEX_SHADER, A::UNIFORM_READ, L::UNDEFINED)
+            }
+            Self::VertexShaderReadSampledImageOrUniformTexelBuffer => (
+                S::VERTEX_SHADER,
+                A::SHADER_READ,
+                L::SHADER_READ_ONLY_OPTIMAL,
+            ),
+            Self::VertexShaderReadOther => (S::VERTEX_SHADER, A::SHADER_READ, L::GENERAL),
+            Self::TessellationControlShaderReadUniformBuffer => (
+                S::TESSELLATION_CONTROL_SHADER,
+                A::UNIFORM_READ,
+                L::UNDEFINED,
+            ),
+            Self::TessellationControlShaderReadSampledImageOrUniformTexelBuffer => (
+                S::TESSELLATION_CONTROL_SHADER,
+                A::SHADER_READ,
+                L::SHADER_READ_ONLY_OPTIMAL,
+            ),
+            Self::TessellationControlShaderReadOther => {
+                (S::TESSELLATION_CONTROL_SHADER, A::SHADER_READ, L::GENERAL)
+            }
+            Self::TessellationEvaluationShaderReadUniformBuffer => (
+                S::TESSELLATION_EVALUATION_SHADER,
+                A::UNIFORM_READ,
+                L::UNDEFINED,
+            ),
+            Self::TessellationEvaluationShaderReadSampledImageOrUniformTexelBuffer => (
+                S::TESSELLATION_EVALUATION_SHADER,
+                A::SHADER_READ,
+                L::SHADER_READ_ONLY_OPTIMAL,
+            ),
+            Self::TessellationEvaluationShaderReadOther => (
+                S::TESSELLATION_EVALUATION_SHADER,
+                A::SHADER_READ,
+                L::GENERAL,
+            ),
+            Self::GeometryShaderReadUniformBuffer => {
+                (S::GEOMETRY_SHADER, A::UNIFORM_READ, L::UNDEFINED)
+            }
+            Self::GeometryShaderReadSampledImageOrUniformTexelBuffer => (
+                S::GEOMETRY_SHADER,
+                A::SHADER_READ,
+                L::SHADER_READ_ONLY_OPTIMAL,
+            ),
+            Self::GeometryShaderReadOther => (S::GEOMETRY_SHADER, A::SHADER_READ, L::GENERAL),
+            Self::FragmentShaderReadUniformBuffer => {
+                (S::FRAGMENT_SHADER, A::UNIFORM_READ, L::UNDEFINED)
+            }
+            Self::FragmentShaderReadSampledImageOrUniformTexelBuffer => (
+                S::FRAGMENT_SHADER,
+                A::SHADER_READ,
+                L::SHADER_READ_ONLY_OPTIMAL,
+            ),
+            Self::FragmentShaderReadColorInputAttachment => (
+                S::FRAGMENT_SHADER,
+                A::INPUT_ATTACHMENT_READ,
+                L::SHADER_READ_ONLY_OPTIMAL,
+            ),
+            Self::FragmentShaderReadDepthStencilInputAttachment => (
+                S::FRAGMENT_SHADER,
+                A::INPUT_ATTACHMENT_READ,
+                L::DEPTH_STENCIL_READ_ONLY_OPTIMAL,
+            ),
+            Self::FragmentShaderReadOther => (S::FRAGMENT_SHADER, A::SHADER_READ, L::GENERAL),
+            Self::ColorAttachmentRead => (
+                S::COLOR_ATTACHMENT_OUTPUT,
+                A::COLOR_ATTACHMENT_READ,
+                L::COLOR_ATTACHMENT_OPTIMAL,
+            ),
+            Self::DepthStencilAttachmentRead => (
+                S::EARLY_FRAGMENT_TESTS | S::LATE_FRAGMENT_TESTS,
+                A::DEPTH_STENCIL_ATTACHMENT_READ,
+                L::DEPTH_STENCIL_READ_ONLY_OPTIMAL,
+            ),
+            Self::ComputeShaderReadUniformBuffer => {
+                (S::COMPUTE_SHADER, A::UNIFORM_READ, L::UNDEFINED)
+            }
+            Self::ComputeShaderReadSampledImageOrUniformTexelBuffer => (
+                S::COMPUTE_SHADER,
+                A::SHADER_READ,
+                L::SHADER_READ_ONLY_OPTIMAL,
+            ),
+            Self::ComputeShaderReadOther => (S::COMPUTE_SHADER, A::SHADER_READ, L::GENERAL),
+            Self::AnyShaderReadUniformBuffer => (S::ALL_COMMANDS, A::UNIFORM_READ, L::UNDEFINED),
+            Self::AnyShaderReadUniformBufferOrVertexBuffer => (
+                S::ALL_COMMANDS,
+                A::UNIFORM_READ | A::VERTEX_ATTRIBUTE_READ,
+                L::UNDEFINED,
+            ),
+            Self::AnyShaderReadSampledImageOrUniformTexelBuffer => {
+                (S::ALL_COMMANDS, A::SHADER_READ, L::SHADER_READ_ONLY_OPTIMAL)
+            }
+            Self::AnyShaderReadOther => (S::ALL_COMMANDS, A::SHADER_READ, L::GENERAL),
+            Self::TransferRead => (S::TRANSFER, A::TRANSFER_READ, L::TRANSFER_SRC_OPTIMAL),
+            Self::HostRead => (S::HOST, A::HOST_READ, L::GENERAL),
+            Self::Present => (S::empty(), A::empty(), L::PRESENT_SRC_KHR),
+
+            Self::VertexShaderWrite => (S::VERTEX_SHADER, A::SHADER_WRITE, L::GENERAL),
+            Self::TessellationControlShaderWrite => {
+                (S::TESSELLATION_CONTROL_SHADER, A::SHADER_WRITE, L::GENERAL)
+            }
+            Self::TessellationEvaluationShaderWrite => (
+                S::TESSELLATION_EVALUATION_SHADER,
+                A::SHADER_WRITE,
+                L::GENERAL,
+            ),
+            Self::GeometryShaderWrite => (S::GEOMETRY_SHADER, A::SHADER_WRITE, L::GENERAL),
+            Self::FragmentShaderWrite => (S::FRAGMENT_SHADER, A::SHADER_WRITE, L::GENERAL),
+            Self::ColorAttachmentWrite => (
+                S::COLOR_ATTACHMENT_OUTPUT,
+                A::COLOR_ATTACHMENT_WRITE,
+                L::COLOR_ATTACHMENT_OPTIMAL,
+            ),
+            Self::DepthStencilAttachmentWrite => (
+                S::EARLY_FRAGMENT_TESTS | S::LATE_FRAGMENT_TESTS,
+                A::DEPTH_STENCIL_ATTACHMENT_WRITE,
+                L::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+            ),
+            Self::DepthAttachmentWriteStencilReadOnly => (
+                S::EARLY_FRAGMENT_TESTS | S::LATE_FRAGMENT_TESTS,
+                A::DEPTH_STENCIL_ATTACHMENT_WRITE | A::DEPTH_STENCIL_ATTACHMENT_READ,
+                L::DEPTH_ATTACHMENT_STENCIL_READ_ONLY_OPTIMAL,
+            ),
+            Self::StencilAttachmentWriteDepthReadOnly => (
+                S::EARLY_FRAGMENT_TESTS | S::LATE_FRAGMENT_TESTS,
+                A::DEPTH_STENCIL_ATTACHMENT_WRITE | A::DEPTH_STENCIL_ATTACHMENT_READ,
+                L::DEPTH_READ_ONLY_STENCIL_ATTACHMENT_OPTIMAL,
+            ),
+            Self::ComputeShaderWrite => (S::COMPUTE_SHADER, A::SHADER_WRITE, L::GENERAL),
+            Self::AnyShaderWrite => (S::ALL_COMMANDS, A::SHADER_WRITE, L::GENERAL),
+            Self::TransferWrite => (S::TRANSFER, A::TRANSFER_WRITE, L::TRANSFER_DST_OPTIMAL),
+            Self::HostPreinitialized => (S::HOST, A::HOST_WRITE, L::PREINITIALIZED),
+            Self::HostWrite => (S::HOST, A::HOST_WRITE, L::GENERAL),
+            Self::ColorAttachmentReadWrite => (
+                S::COLOR_ATTACHMENT_OUTPUT,
+                A::COLOR_ATTACHMENT_READ | A::COLOR_ATTACHMENT_WRITE,
+                L::COLOR_ATTACHMENT_OPTIMAL,
+            ),
+            Self::General => (
+                S::ALL_COMMANDS,
+                A::MEMORY_READ | A::MEMORY_WRITE,
+                L::GENERAL,
+            ),
+        };
+        AccessInfo {
+            stage_mask,
+            access_mask,
+            image_layout,
+        }
+    }
+
+    /// Whether this access modifies the resource.
+    pub fn is_write(self) -> bool {
+        matches!(
+            self,
+            Self::VertexShaderWrite
+                | Self::TessellationControlShaderWrite
+                | Self::TessellationEvaluationShaderWrite
+                | Self::GeometryShaderWrite
+                | Self::FragmentShaderWrite
+                | Self::ColorAttachmentWrite
+                | Self::DepthStencilAttachmentWrite
+                | Self::DepthAttachmentWriteStencilReadOnly
+                | Self::StencilAttachmentWriteDepthReadOnly
+                | Self::ComputeShaderWrite
+                | Self::AnyShaderWrite
+                | Self::TransferWrite
+                | Self::HostPreinitialized
+                | Self::HostWrite
+                | Self::ColorAttachmentReadWrite
+                | Self::General
+        )
+    }
+}
+
+/// Combined stages, access flags and image layout of a set of accesses that happen together.
+///
+/// The layout is the common layout of all accesses that care about it, or
+/// [`vk::ImageLayout::GENERAL`] if they disagree.
+pub(crate) fn combine(accesses: &[AccessType]) -> AccessInfo {
+    let mut combined = AccessInfo::default();
+    for access in accesses {
+        let info = access.info();
+        combined.stage_mask |= info.stage_mask;
+        combined.access_mask |= info.access_mask;
+        combined.image_layout = match (combined.image_layout, info.image_layout) {
+            (vk::ImageLayout::UNDEFINED, layout) => layout,
+            (layout, vk::ImageLayout::UNDEFINED) => layout,
+            (a, b) if a == b => a,
+            _ => vk::ImageLayout::GENERAL,
+        };
+    }
+    combined
+}
+
+/// Converts legacy stage flags to `VK_KHR_synchronization2` flags, which share their bit values.
+pub(crate) fn stage_flags2(stage_mask: vk::PipelineStageFlags) -> vk::PipelineStageFlags2KHR {
+    vk::PipelineStageFlags2KHR::from_raw(u64::from(stage_mask.as_raw()))
+}
+
+/// Converts legacy access flags to `VK_KHR_synchronization2` flags, which share their bit values.
+pub(crate) fn access_flags2(access_mask: vk::AccessFlags) -> vk::AccessFlags2KHR {
+    vk::AccessFlags2KHR::from_raw(u64::from(access_mask.as_raw()))
+}
@@ -0,0 +1,459 @@
+use super::access::{access_flags2, combine, stage_flags2, AccessType};
+use crate::extensions::khr::Synchronization2;
+use crate::vk;
+use crate::Device;
+
+/// Which layouts an image is kept in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ImageLayout {
+    /// Use the optimal layout of every access, such as
+    /// [`vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL`] for [`AccessType::ColorAttachmentWrite`].
+    #[default]
+    Optimal,
+    /// Use [`vk::ImageLayout::GENERAL`] for everything but [`AccessType::Present`].
+    General,
+    /// Use [`vk::ImageLayout::SHARED_PRESENT_KHR`] for everything, as required for shared
+    /// presentable images.
+    GeneralAndPresentation,
+}
+
+/// Global memory barrier between two sets of accesses.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GlobalBarrier<'a> {
+    pub previous_accesses: &'a [AccessType],
+    pub next_accesses: &'a [AccessType],
+}
+
+/// Buffer memory barrier between two sets of accesses.
+#[derive(Clone, Copy, Debug)]
+pub struct BufferBarrier<'a> {
+    pub previous_accesses: &'a [AccessType],
+    pub next_accesses: &'a [AccessType],
+    pub src_queue_family_index: u32,
+    pub dst_queue_family_index: u32,
+    pub buffer: vk::Buffer,
+    pub offset: vk::DeviceSize,
+    pub size: vk::DeviceSize,
+}
+
+impl Default for BufferBarrier<'_> {
+    fn default() -> Self {
+        Self {
+            previous_accesses: &[],
+            next_accesses: &[],
+            src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+            dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+            buffer: vk::Buffer::null(),
+            offset: 0,
+            size: vk::WHOLE_SIZE,
+        }
+    }
+}
+
+/// Image memory barrier between two sets of accesses, including the layout transition they
+/// require.
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct ImageBarrier<'a> {
+    pub previous_accesses: &'a [AccessType],
+    pub next_accesses: &'a [AccessType],
+    pub previous_layout: ImageLayout,
+    pub next_layout: ImageLayout,
+    /// Transition from [`vk::ImageLayout::UNDEFINED`], discarding the current contents.
+    pub discard_contents: bool,
+    pub src_queue_family_index: u32,
+    pub dst_queue_family_index: u32,
+    pub image: vk::Image,
+    pub range: vk::ImageSubresourceRange,
+}
+
+impl Default for ImageBarrier<'_> {
+    fn default() -> Self {
+        Self {
+            previous_accesses: &[],
+            next_accesses: &[],
+            previous_layout: ImageLayout::Optimal,
+            next_layout: ImageLayout::Optimal,
+            discard_contents: false,
+            src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+            dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+            image: vk::Image::null(),
+            range: vk::ImageSubresourceRange::default(),
+        }
+    }
+}
+
+/// Stages and access masks of a barrier, before defaulting empty stage masks.
+#[derive(Clone, Copy, Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+struct Masks {
+    src_stage_mask: vk::PipelineStageFlags,
+    src_access_mask: vk::AccessFlags,
+    dst_stage_mask: vk::PipelineStageFlags,
+    dst_access_mask: vk::AccessFlags,
+}
+
+impl Masks {
+    fn new(previous_accesses: &[AccessType], next_accesses: &[AccessType]) -> Self {
+        let mut masks = Self::default();
+        for access in previous_accesses {
+            let info = access.info();
+            masks.src_stage_mask |= info.stage_mask;
+            // Reads never need to be made available.
+            if access.is_write() {
+                masks.src_access_mask |= info.access_mask;
+            }
+        }
+        let next = combine(next_accesses);
+        masks.dst_stage_mask = next.stage_mask;
+        // Only writes need to be made visible to the next accesses.
+        if !masks.src_access_mask.is_empty() {
+            masks.dst_access_mask = next.access_mask;
+        }
+        masks
+    }
+
+    fn with_layout_transition(mut self, next_accesses: &[AccessType]) -> Self {
+        // Layout transitions are writes that need to be made visible.
+        self.dst_access_mask = combine(next_accesses).access_mask;
+        self
+    }
+
+    /// Stage masks for `vkCmdPipelineBarrier`, which may not be empty.
+    fn stage_masks(&self) -> (vk::PipelineStageFlags, vk::PipelineStageFlags) {
+        let src = if self.src_stage_mask.is_empty() {
+            vk::PipelineStageFlags::TOP_OF_PIPE
+        } else {
+            self.src_stage_mask
+        };
+        let dst = if self.dst_stage_mask.is_empty() {
+            vk::PipelineStageFlags::BOTTOM_OF_PIPE
+        } else {
+            self.dst_stage_mask
+        };
+        (src, dst)
+    }
+}
+
+fn image_layout(accesses: &[AccessType], layout: ImageLayout) -> vk::ImageLayout {
+    let optimal = combine(accesses).image_layout;
+    match (layout, optimal) {
+        (_, vk::ImageLayout::UNDEFINED) => vk::ImageLayout::UNDEFINED,
+        (ImageLayout::Optimal, optimal) => optimal,
+        (ImageLayout::General, vk::ImageLayout::PRESENT_SRC_KHR) => {
+            vk::ImageLayout::PRESENT_SRC_KHR
+        }
+        (ImageLayout::General, _) => vk::ImageLayout::GENERAL,
+        (ImageLayout::GeneralAndPresentation, _) => vk::ImageLayout::SHARED_PRESENT_KHR,
+    }
+}
+
+impl ImageBarrier<'_> {
+    fn layouts(&self) -> (vk::ImageLayout, vk::ImageLayout) {
+        let old_layout = if self.discard_contents {
+            vk::ImageLayout::UNDEFINED
+        } else {
+            image_layout(self.previous_accesses, self.previous_layout)
+        };
+        (
+            old_layout,
+            image_layout(self.next_accesses, self.next_layout),
+        )
+    }
+
+    fn masks(&self) -> Masks {
+        let masks = Masks::new(self.previous_accesses, self.next_accesses);
+        let (old_layout, new_layout) = self.layouts();
+        if old_layout != new_layout || self.src_queue_family_index != self.dst_queue_family_index {
+            masks.with_layout_transition(self.next_accesses)
+        } else {
+            masks
+        }
+    }
+}
+
+/// Returns the source and destination stage masks and the [`vk::MemoryBarrier`] separating
+/// `barrier.previous_accesses` from `barrier.next_accesses`.
+pub fn get_memory_barrier(
+    barrier: &GlobalBarrier,
+) -> (
+    vk::PipelineStageFlags,
+    vk::PipelineStageFlags,
+    vk::MemoryBarrier,
+) {
+    let masks = Masks::new(barrier.previous_accesses, barrier.next_accesses);
+    let (src_stage_mask, dst_stage_mask) = masks.stage_masks();
+    let memory_barrier = vk::MemoryBarrier::builder()
+        .src_access_mask(masks.src_access_mask)
+        .dst_access_mask(masks.dst_access_mask)
+        .build();
+    (src_stage_mask, dst_stage_mask, memory_barrier)
+}
+
+/// Returns the source and destination stage masks and the [`vk::BufferMemoryBarrier`]
+/// described by `barrier`.
+pub fn get_buffer_memory_barrier(
+    barrier: &BufferBarrier,
+) -> (
+    vk::PipelineStageFlags,
+    vk::PipelineStageFlags,
+    vk::BufferMemoryBarrier,
+) {
+    let masks = Masks::new(barrier.previous_accesses, barrier.next_accesses);
+    let (src_stage_mask, dst_stage_mask) = masks.stage_masks();
+    let buffer_barrier = vk::BufferMemoryBarrier::builder()
+        .src_access_mask(masks.src_access_mask)
+        .dst_access_mask(masks.dst_access_mask)
+        .src_queue_family_index(barrier.src_queue_family_index)
+        .dst_queue_family_index(barrier.dst_queue_family_index)
+        .buffer(barrier.buffer)
+        .offset(barrier.offset)
+        .size(barrier.size)
+        .build();
+    (src_stage_mask, dst_stage_mask, buffer_barrier)
+}
+
+/// Returns the source and destination stage masks and the [`vk::ImageMemoryBarrier`]
+/// described by `barrier`.
+pub fn get_image_memory_barrier(
+    barrier: &ImageBarrier,
+) -> (
+    vk::PipelineStageFlags,
+    vk::PipelineStageFlags,
+    vk::ImageMemoryBarrier,
+) {
+    let masks = barrier.masks();
+    let (src_stage_mask, dst_stage_mask) = masks.stage_masks();
+    let (old_layout, new_layout) = barrier.layouts();
+    let image_barrier = vk::ImageMemoryBarrier::builder()
+        .src_access_mask(masks.src_access_mask)
+        .dst_access_mask(masks.dst_access_mask)
+        .old_layout(old_layout)
+        .new_layout(new_layout)
+        .src_queue_family_index(barrier.src_queue_family_index)
+        .dst_queue_family_index(barrier.dst_queue_family_index)
+        .image(barrier.image)
+        .subresource_range(barrier.range)
+        .build();
+    (src_stage_mask, dst_stage_mask, image_barrier)
+}
+
+/// Returns the `VK_KHR_synchronization2` equivalent of [`get_memory_barrier()`].
+pub fn get_memory_barrier2(barrier: &GlobalBarrier) -> vk::MemoryBarrier2KHR {
+    let masks = Masks::new(barrier.previous_accesses, barrier.next_accesses);
+    vk::MemoryBarrier2KHR::builder()
+        .src_stage_mask(stage_flags2(masks.src_stage_mask))
+        .src_access_mask(access_flags2(masks.src_access_mask))
+        .dst_stage_mask(stage_flags2(masks.dst_stage_mask))
+        .dst_access_mask(access_flags2(masks.dst_access_mask))
+        .build()
+}
+
+/// Returns the `VK_KHR_synchronization2` equivalent of [`get_buffer_memory_barrier()`].
+pub fn get_buffer_memory_barrier2(barrier: &BufferBarrier) -> vk::BufferMemoryBarrier2KHR {
+    let masks = Masks::new(barrier.previous_accesses, barrier.next_accesses);
+    vk::BufferMemoryBarrier2KHR::builder()
+        .src_stage_mask(stage_flags2(masks.src_stage_mask))
+        .src_access_mask(access_flags2(masks.src_access_mask))
+        .dst_stage_mask(stage_flags2(masks.dst_stage_mask))
+        .dst_access_mask(access_flags2(masks.dst_access_mask))
+        .src_queue_family_index(barrier.src_queue_family_index)
+        .dst_queue_family_index(barrier.dst_queue_family_index)
+        .buffer(barrier.buffer)
+        .offset(barrier.offset)
+        .size(barrier.size)
+        .build()
+}
+
+/// Returns the `VK_KHR_synchronization2` equivalent of [`get_image_memory_barrier()`].
+pub fn get_image_memory_barrier2(barrier: &ImageBarrier) -> vk::ImageMemoryBarrier2KHR {
+    let masks = barrier.masks();
+    let (old_layout, new_layout) = barrier.layouts();
+    vk::ImageMemoryBarrier2KHR::builder()
+        .src_stage_mask(stage_flags2(masks.src_stage_mask))
+        .src_access_mask(access_flags2(masks.src_access_mask))
+        .dst_stage_mask(stage_flags2(masks.dst_stage_mask))
+        .dst_access_mask(access_flags2(masks.dst_access_mask))
+        .old_layout(old_layout)
+        .new_layout(new_layout)
+        .src_queue_family_index(barrier.src_queue_family_index)
+        .dst_queue_family_index(barrier.dst_queue_family_index)
+        .image(barrier.image)
+        .subresource_range(barrier.range)
+        .build()
+}
+
+/// Records all given barriers with a single [`Device::cmd_pipeline_barrier()`] call.
+///
+/// ```no_run
+/// # use ash::{sync::{self, AccessType}, vk};
+/// # unsafe fn record(device: &ash::Device, command_buffer: vk::CommandBuffer, image: vk::Image) {
+/// sync::cmd_pipeline_barrier(
+///     device,
+///     command_buffer,
+///     None,
+///     &[],
+///     &[sync::ImageBarrier {
+///         previous_accesses: &[AccessType::ColorAttachmentWrite],
+///         next_accesses: &[AccessType::Present],
+///         image,
+///         range: vk::ImageSubresourceRange {
+///             aspect_mask: vk::ImageAspectFlags::COLOR,
+///             level_count: 1,
+///             layer_count: 1,
+///             ..Default::default()
+///         },
+///         ..Default::default()
+///     }],
+/// );
+/// # }
+/// ```
+pub unsafe fn cmd_pipeline_barrier(
+    device: &Device,
+    command_buffer: vk::CommandBuffer,
+    global_barrier: Option<&GlobalBarrier>,
+    buffer_barriers: &[BufferBarrier],
+    image_barriers: &[ImageBarrier],
+) {
+    let mut src_stage_mask = vk::PipelineStageFlags::empty();
+    let mut dst_stage_mask = vk::PipelineStageFlags::empty();
+
+    let memory_barriers = global_barrier
+        .map(|barrier| {
+            let (src, dst, memory_barrier) = get_memory_barrier(barrier);
+            src_stage_mask |= src;
+            dst_stage_mask |= dst;
+            memory_barrier
+        })
+        .into_iter()
+        .collect::<Vec<_>>();
+    let buffer_memory_barriers = buffer_barriers
+        .iter()
+        .map(|barrier| {
+            let (src, dst, buffer_barrier) = get_buffer_memory_barrier(barrier);
+            src_stage_mask |= src;
+            dst_stage_mask |= dst;
+            buffer_barrier
+        })
+        .collect::<Vec<_>>();
+    let image_memory_barriers = image_barriers
+        .iter()
+        .map(|barrier| {
+            let (src, dst, image_barrier) = get_image_memory_barrier(barrier);
+            src_stage_mask |= src;
+            dst_stage_mask |= dst;
+            image_barrier
+        })
+        .collect::<Vec<_>>();
+
+    if src_stage_mask.is_empty() {
+        src_stage_mask = vk::PipelineStageFlags::TOP_OF_PIPE;
+    }
+    if dst_stage_mask.is_empty() {
+        dst_stage_mask = vk::PipelineStageFlags::BOTTOM_OF_PIPE;
+    }
+    device.cmd_pipeline_barrier(
+        command_buffer,
+        src_stage_mask,
+        dst_stage_mask,
+        vk::DependencyFlags::empty(),
+        &memory_barriers,
+        &buffer_memory_barriers,
+        &image_memory_barriers,
+    );
+}
+
+/// Records all given barriers with a single [`Synchronization2::cmd_pipeline_barrier2()`] call,
+/// keeping the individual stage masks of every barrier.
+pub unsafe fn cmd_pipeline_barrier2(
+    synchronization2: &Synchronization2,
+    command_buffer: vk::CommandBuffer,
+    global_barrier: Option<&GlobalBarrier>,
+    buffer_barriers: &[BufferBarrier],
+    image_barriers: &[ImageBarrier],
+) {
+    let memory_barriers = global_barrier
+        .map(get_memory_barrier2)
+        .into_iter()
+        .collect::<Vec<_>>();
+    let buffer_memory_barriers = buffer_barriers
+        .iter()
+        .map(get_buffer_memory_barrier2)
+        .collect::<Vec<_>>();
+    let image_memory_barriers = image_barriers
+        .iter()
+        .map(get_image_memory_barrier2)
+        .collect::<Vec<_>>();
+    let dependency_info = vk::DependencyInfoKHR::builder()
+        .memory_barriers(&memory_barriers)
+        .buffer_memory_barriers(&buffer_memory_barriers)
+        .image_memory_barriers(&image_memory_barriers);
+    synchronization2.cmd_pipeline_barrier2(command_buffer, &dependency_info);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_attachment_to_present() {
+        let (src, dst, barrier) = get_image_memory_barrier(&ImageBarrier {
+            previous_accesses: &[AccessType::ColorAttachmentWrite],
+            next_accesses: &[AccessType::Present],
+            ..Default::default()
+        });
+        assert!(src == vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT);
+        assert!(dst == vk::PipelineStageFlags::BOTTOM_OF_PIPE);
+        assert!(barrier.src_access_mask == vk::AccessFlags::COLOR_ATTACHMENT_WRITE);
+        assert!(barrier.dst_access_mask == vk::AccessFlags::empty());
+        assert!(barrier.old_layout == vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL);
+        assert!(barrier.new_layout == vk::ImageLayout::PRESENT_SRC_KHR);
+
+        let barrier2 = get_image_memory_barrier2(&ImageBarrier {
+            previous_accesses: &[AccessType::ColorAttachmentWrite],
+            next_accesses: &[AccessType::Present],
+            ..Default::default()
+        });
+        assert!(barrier2.src_stage_mask == vk::PipelineStageFlags2KHR::COLOR_ATTACHMENT_OUTPUT);
+        assert!(barrier2.dst_stage_mask == vk::PipelineStageFlags2KHR::NONE);
+        assert!(barrier2.src_access_mask == vk::AccessFlags2KHR::COLOR_ATTACHMENT_WRITE);
+    }
+
+    #[test]
+    fn read_after_read_needs_no_memory_dependency() {
+        let (src, dst, barrier) = get_memory_barrier(&GlobalBarrier {
+            previous_accesses: &[AccessType::VertexBuffer],
+            next_accesses: &[AccessType::IndexBuffer],
+        });
+        assert!(src == vk::PipelineStageFlags::VERTEX_INPUT);
+        assert!(dst == vk::PipelineStageFlags::VERTEX_INPUT);
+        assert!(barrier.src_access_mask.is_empty());
+        assert!(barrier.dst_access_mask.is_empty());
+    }
+
+    #[test]
+    fn write_after_read_needs_only_execution_dependency() {
+        let (src, dst, barrier) = get_memory_barrier(&GlobalBarrier {
+            previous_accesses: &[AccessType::VertexBuffer],
+            next_accesses: &[AccessType::ComputeShaderWrite],
+        });
+        assert!(src == vk::PipelineStageFlags::VERTEX_INPUT);
+        assert!(dst == vk::PipelineStageFlags::COMPUTE_SHADER);
+        assert!(barrier.src_access_mask.is_empty());
+        assert!(barrier.dst_access_mask.is_empty());
+    }
+
+    #[test]
+    fn general_layout() {
+        let (_, _, barrier) = get_image_memory_barrier(&ImageBarrier {
+            previous_accesses: &[AccessType::TransferWrite],
+            next_accesses: &[AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer],
+            previous_layout: ImageLayout::General,
+            next_layout: ImageLayout::General,
+            ..Default::default()
+        });
+        assert!(barrier.old_layout == vk::ImageLayout::GENERAL);
+        assert!(barrier.new_layout == vk::ImageLayout::GENERAL);
+        assert!(barrier.dst_access_mask == vk::AccessFlags::SHADER_READ);
+    }
+}
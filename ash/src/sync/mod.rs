@@ -0,0 +1,23 @@
+//! Pipeline barrier helpers built around a list of high-level [`AccessType`]s.
+//!
+//! Every [`AccessType`] describes one way a resource is used, such as "sampled in a fragment
+//! shader" or "written as a color attachment", and maps to the pipeline stages, access flags and
+//! image layout that use requires. [`ResourceTracker`] remembers the last use of every image
+//! subresource and buffer range and emits the barriers needed to transition to the next use.
+//!
+//! When the previous use of a resource is already known, [`cmd_pipeline_barrier()`] and
+//! [`cmd_pipeline_barrier2()`] record the barrier between two sets of accesses directly, without
+//! keeping any state.
+
+pub use self::access::{AccessInfo, AccessType};
+pub use self::barrier::{
+    cmd_pipeline_barrier, cmd_pipeline_barrier2, get_buffer_memory_barrier,
+    get_buffer_memory_barrier2, get_image_memory_barrier, get_image_memory_barrier2,
+    get_memory_barrier, get_memory_barrier2, BufferBarrier, GlobalBarrier, ImageBarrier,
+    ImageLayout,
+};
+pub use self::tracker::ResourceTracker;
+
+mod access;
+mod barrier;
+mod tracker;
@@ -0,0 +1,723 @@
+use super::access::{access_flags2, combine, stage_flags2, AccessInfo, AccessType};
+use crate::extensions::khr::Synchronization2;
+use crate::vk;
+use crate::Device;
+use std::collections::HashMap;
+
+/// Source stages and access flags of a barrier.
+type Source = (vk::PipelineStageFlags, vk::AccessFlags);
+
+/// Synchronization state of an image subresource or buffer range.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+struct State {
+    layout: vk::ImageLayout,
+    /// Stages and accesses of the last write (or layout transition).
+    write_stages: vk::PipelineStageFlags,
+    write_access: vk::AccessFlags,
+    /// Stages that read the resource since the last write; a later write has to wait for them.
+    read_stages: vk::PipelineStageFlags,
+    /// Stages and accesses the last write has already been made visible to.
+    visible_stages: vk::PipelineStageFlags,
+    visible_access: vk::AccessFlags,
+}
+
+impl State {
+    fn with_layout(layout: vk::ImageLayout) -> Self {
+        Self {
+            layout,
+            ..Default::default()
+        }
+    }
+
+    /// Transitions to `next`, returning the source stages and access of the required barrier
+    /// (if any) and whether the layout changes.
+    fn transition(
+        &mut self,
+        next: &AccessInfo,
+        writes: bool,
+        track_layout: bool,
+    ) -> Option<Source> {
+        let new_layout = if track_layout && next.image_layout != vk::ImageLayout::UNDEFINED {
+            next.image_layout
+        } else {
+            self.layout
+        };
+        let layout_change = track_layout && new_layout != self.layout;
+
+        if writes || layout_change {
+            let src_stages = self.write_stages | self.read_stages;
+            let src_access = self.write_access;
+            *self = Self {
+                layout: new_layout,
+                write_stages: next.stage_mask,
+                write_access: if writes {
+                    next.access_mask
+                } else {
+                    vk::AccessFlags::empty()
+                },
+                read_stages: vk::PipelineStageFlags::empty(),
+                visible_stages: next.stage_mask,
+                visible_access: next.access_mask,
+            };
+            if writes {
+                // The written data itself still has to be made visible to later readers.
+                self.visible_stages = vk::PipelineStageFlags::empty();
+                self.visible_access = vk::AccessFlags::empty();
+            } else {
+                self.read_stages = next.stage_mask;
+            }
+            if layout_change || !src_stages.is_empty() {
+                return Some((src_stages, src_access));
+            }
+            return None;
+        }
+
+        let visible = self.visible_stages.contains(next.stage_mask)
+            && self.visible_access.contains(next.access_mask);
+        let barrier = if self.write_stages.is_empty() || visible {
+            None
+        } else {
+            self.visible_stages |= next.stage_mask;
+            self.visible_access |= next.access_mask;
+            Some((self.write_stages, self.write_access))
+        };
+        self.read_stages |= next.stage_mask;
+        barrier
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+struct ImageState {
+    mip_levels: u32,
+    array_layers: u32,
+    /// One entry per subresource, indexed by `mip_level * array_layers + array_layer`.
+    subresources: Vec<State>,
+}
+
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+struct BufferState {
+    /// Sorted, non-overlapping `(start, end, state)` ranges. Gaps have never been accessed.
+    ranges: Vec<(vk::DeviceSize, vk::DeviceSize, State)>,
+}
+
+impl BufferState {
+    /// Splits tracked ranges at `start` and `end` and fills the gaps in between, returning the
+    /// index range of the entries covering `start..end`.
+    fn isolate(&mut self, start: vk::DeviceSize, end: vk::DeviceSize) -> std::ops::Range<usize> {
+        let mut ranges = Vec::with_capacity(self.ranges.len() + 3);
+        let mut cursor = start;
+        for &(s, e, state) in &self.ranges {
+            if e <= start || s >= end {
+                ranges.push((s, e, state));
+                continue;
+            }
+            if s < start {
+                ranges.push((s, start, state));
+            }
+            let (overlap_start, overlap_end) = (s.max(start), e.min(end));
+            if cursor < overlap_start {
+                ranges.push((cursor, overlap_start, State::default()));
+            }
+            ranges.push((overlap_start, overlap_end, state));
+            cursor = overlap_end;
+            if e > end {
+                ranges.push((end, e, state));
+            }
+        }
+        if cursor < end {
+            ranges.push((cursor, end, State::default()));
+        }
+        ranges.sort_by_key(|&(s, _, _)| s);
+        self.ranges = ranges;
+
+        let first = self.ranges.iter().position(|&(s, _, _)| s >= start);
+        let last = self.ranges.iter().position(|&(s, _, _)| s >= end);
+        let len = self.ranges.len();
+        first.unwrap_or(len)..last.unwrap_or(len)
+    }
+
+    /// Merges adjacent ranges that ended up in the same state.
+    fn coalesce(&mut self) {
+        self.ranges.dedup_by(|next, prev| {
+            if prev.1 == next.0 && prev.2 == next.2 {
+                prev.1 = next.1;
+                true
+            } else {
+                false
+            }
+        });
+    }
+}
+
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+enum BarrierKind {
+    /// Execution or global memory dependency.
+    Global,
+    Buffer {
+        buffer: vk::Buffer,
+        offset: vk::DeviceSize,
+        size: vk::DeviceSize,
+    },
+    Image {
+        image: vk::Image,
+        range: vk::ImageSubresourceRange,
+        old_layout: vk::ImageLayout,
+        new_layout: vk::ImageLayout,
+    },
+}
+
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+struct PendingBarrier {
+    src_stage_mask: vk::PipelineStageFlags,
+    src_access_mask: vk::AccessFlags,
+    dst_stage_mask: vk::PipelineStageFlags,
+    dst_access_mask: vk::AccessFlags,
+    kind: BarrierKind,
+}
+
+/// Tracks the layout and pending accesses of images and buffers, and generates the barriers
+/// needed to move them to their next use.
+///
+/// Declare every use of a resource with [`ResourceTracker::use_image()`] or
+/// [`ResourceTracker::use_buffer()`] before recording the commands that access it, then record
+/// the accumulated barriers with [`ResourceTracker::flush()`] (or
+/// [`ResourceTracker::flush2()`] for `VK_KHR_synchronization2`). A resource must not be used
+/// twice without a flush in between.
+///
+/// Depth and stencil aspects of an image share their state.
+///
+/// ```no_run
+/// # use ash::{sync::{AccessType, ResourceTracker}, vk};
+/// # unsafe fn record(device: &ash::Device, command_buffer: vk::CommandBuffer, image: vk::Image) {
+/// let range = vk::ImageSubresourceRange {
+///     aspect_mask: vk::ImageAspectFlags::COLOR,
+///     base_mip_level: 0,
+///     level_count: 1,
+///     base_array_layer: 0,
+///     layer_count: 1,
+/// };
+/// let mut tracker = ResourceTracker::new();
+/// tracker.register_image(image, 1, 1, vk::ImageLayout::UNDEFINED);
+/// tracker.use_image(image, range, &[AccessType::ColorAttachmentWrite]);
+/// tracker.flush(device, command_buffer);
+/// // ... render to the image ...
+/// tracker.use_image(image, range, &[AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer]);
+/// tracker.flush(device, command_buffer);
+/// # }
+/// ```
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct ResourceTracker {
+    images: HashMap<vk::Image, ImageState>,
+    buffers: HashMap<vk::Buffer, BufferState>,
+    pending: Vec<PendingBarrier>,
+}
+
+impl ResourceTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts tracking `image`, whose subresources are all in `initial_layout`.
+    pub fn register_image(
+        &mut self,
+        image: vk::Image,
+        mip_levels: u32,
+        array_layers: u32,
+        initial_layout: vk::ImageLayout,
+    ) {
+        self.images.insert(
+            image,
+            ImageState {
+                mip_levels,
+                array_layers,
+                subresources: vec![
+                    State::with_layout(initial_layout);
+                    (mip_levels * array_layers) as usize
+                ],
+            },
+        );
+    }
+
+    pub fn forget_image(&mut self, image: vk::Image) {
+        self.images.remove(&image);
+    }
+
+    pub fn forget_buffer(&mut self, buffer: vk::Buffer) {
+        self.buffers.remove(&buffer);
+    }
+
+    /// Current layout of a subresource, if `image` is tracked.
+    pub fn image_layout(
+        &self,
+        image: vk::Image,
+        mip_level: u32,
+        array_layer: u32,
+    ) -> Option<vk::ImageLayout> {
+        let state = self.images.get(&image)?;
+        state
+            .subresources
+            .get((mip_level * state.array_layers + array_layer) as usize)
+            .map(|state| state.layout)
+    }
+
+    /// Marks the contents of `range` as no longer needed, so that the next use transitions from
+    /// [`vk::ImageLayout::UNDEFINED`] instead of preserving the data.
+    pub fn discard_image(&mut self, image: vk::Image, range: vk::ImageSubresourceRange) {
+        let state = self
+            .images
+            .get_mut(&image)
+            .expect("image is not registered");
+        let (levels, layers) = resolve_range(state, &range);
+        for mip in levels {
+            for layer in layers.clone() {
+                state.subresources[(mip * state.array_layers + layer) as usize].layout =
+                    vk::ImageLayout::UNDEFINED;
+            }
+        }
+    }
+
+    /// Declares that `range` of `image` is about to be accessed as `accesses`.
+    ///
+    /// # Panics
+    /// Panics if `image` was not registered with [`ResourceTracker::register_image()`].
+    pub fn use_image(
+        &mut self,
+        image: vk::Image,
+        range: vk::ImageSubresourceRange,
+        accesses: &[AccessType],
+    ) {
+        let next = combine(accesses);
+        let writes = accesses.iter().any(|access| access.is_write());
+        let state = self
+            .images
+            .get_mut(&image)
+            .expect("image is not registered");
+        let (levels, layers) = resolve_range(state, &range);
+
+        let first_barrier = self.pending.len();
+        for mip in levels {
+            // Adjacent layers with identical state share one barrier.
+            let mut run: Option<(u32, State, Option<Source>)> = None;
+            for layer in layers.clone() {
+                let subresource =
+                    &mut state.subresources[(mip * state.array_layers + layer) as usize];
+                let old = *subresource;
+                let src = subresource.transition(&next, writes, true);
+                match run {
+                    Some((_, run_old, _)) if run_old == old => {}
+                    _ => {
+                        if let Some((start, run_old, Some(src))) = run {
+                            self.pending.push(image_barrier(
+                                image,
+                                &range,
+                                mip,
+                                start..layer,
+                                &run_old,
+                                src,
+                                &next,
+                            ));
+                        }
+                        run = Some((layer, old, src));
+                    }
+                }
+            }
+            if let Some((start, run_old, Some(src))) = run {
+                self.pending.push(image_barrier(
+                    image,
+                    &range,
+                    mip,
+                    start..layers.end,
+                    &run_old,
+                    src,
+                    &next,
+                ));
+            }
+        }
+        merge_mip_levels(&mut self.pending, first_barrier);
+    }
+
+    /// Declares that `size` bytes of `buffer` at `offset` are about to be accessed as
+    /// `accesses`. Buffers do not need to be registered.
+    pub fn use_buffer(
+        &mut self,
+        buffer: vk::Buffer,
+        offset: vk::DeviceSize,
+        size: vk::DeviceSize,
+        accesses: &[AccessType],
+    ) {
+        let next = combine(accesses);
+        let writes = accesses.iter().any(|access| access.is_write());
+        let end = if size == vk::WHOLE_SIZE {
+            vk::DeviceSize::MAX
+        } else {
+            offset + size
+        };
+        let state = self.buffers.entry(buffer).or_default();
+
+        let mut src_stage = vk::PipelineStageFlags::empty();
+        let mut src_access = vk::AccessFlags::empty();
+        let mut needed = false;
+        for index in state.isolate(offset, end) {
+            if let Some((stage, access)) = state.ranges[index].2.transition(&next, writes, false) {
+                src_stage |= stage;
+                src_access |= access;
+                needed = true;
+            }
+        }
+        state.coalesce();
+        if needed {
+            self.pending.push(PendingBarrier {
+                src_stage_mask: src_stage,
+                src_access_mask: src_access,
+                dst_stage_mask: next.stage_mask,
+                dst_access_mask: next.access_mask,
+                kind: if src_access.is_empty() {
+                    // Write-after-read hazards only need an execution dependency.
+                    BarrierKind::Global
+                } else {
+                    BarrierKind::Buffer {
+                        buffer,
+                        offset,
+                        size,
+                    }
+                },
+            });
+        }
+    }
+
+    /// Whether any barriers are waiting to be recorded.
+    pub fn has_pending_barriers(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Records all pending barriers as a single [`Device::cmd_pipeline_barrier()`] call.
+    pub unsafe fn flush(&mut self, device: &Device, command_buffer: vk::CommandBuffer) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let mut src_stage_mask = vk::PipelineStageFlags::empty();
+        let mut dst_stage_mask = vk::PipelineStageFlags::empty();
+        let mut memory_barrier = vk::MemoryBarrier::default();
+        let mut buffer_barriers = Vec::new();
+        let mut image_barriers = Vec::new();
+        for barrier in self.pending.drain(..) {
+            src_stage_mask |= barrier.src_stage_mask;
+            dst_stage_mask |= barrier.dst_stage_mask;
+            match barrier.kind {
+                BarrierKind::Global => {
+                    memory_barrier.src_access_mask |= barrier.src_access_mask;
+                    memory_barrier.dst_access_mask |= barrier.dst_access_mask;
+                }
+                BarrierKind::Buffer {
+                    buffer,
+                    offset,
+                    size,
+                } => buffer_barriers.push(
+                    vk::BufferMemoryBarrier::builder()
+                        .src_access_mask(barrier.src_access_mask)
+                        .dst_access_mask(barrier.dst_access_mask)
+                        .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                        .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                        .buffer(buffer)
+                        .offset(offset)
+                        .size(size)
+                        .build(),
+                ),
+                BarrierKind::Image {
+                    image,
+                    range,
+                    old_layout,
+                    new_layout,
+                } => image_barriers.push(
+                    vk::ImageMemoryBarrier::builder()
+                        .src_access_mask(barrier.src_access_mask)
+                        .dst_access_mask(barrier.dst_access_mask)
+                        .old_layout(old_layout)
+                        .new_layout(new_layout)
+                        .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                        .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                        .image(image)
+                        .subresource_range(range)
+                        .build(),
+                ),
+            }
+        }
+        if src_stage_mask.is_empty() {
+            src_stage_mask = vk::PipelineStageFlags::TOP_OF_PIPE;
+        }
+        if dst_stage_mask.is_empty() {
+            dst_stage_mask = vk::PipelineStageFlags::BOTTOM_OF_PIPE;
+        }
+        let memory_barriers: &[vk::MemoryBarrier] = if memory_barrier.src_access_mask.is_empty()
+            && memory_barrier.dst_access_mask.is_empty()
+        {
+            &[]
+        } else {
+            std::slice::from_ref(&memory_barrier)
+        };
+        device.cmd_pipeline_barrier(
+            command_buffer,
+            src_stage_mask,
+            dst_stage_mask,
+            vk::DependencyFlags::empty(),
+            memory_barriers,
+            &buffer_barriers,
+            &image_barriers,
+        );
+    }
+
+    /// Records all pending barriers with [`Synchronization2::cmd_pipeline_barrier2()`], keeping
+    /// the individual stage masks of every barrier.
+    pub unsafe fn flush2(
+        &mut self,
+        synchronization2: &Synchronization2,
+        command_buffer: vk::CommandBuffer,
+    ) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let mut memory_barriers = Vec::new();
+        let mut buffer_barriers = Vec::new();
+        let mut image_barriers = Vec::new();
+        for barrier in self.pending.drain(..) {
+            let src_stage_mask = stage_flags2(barrier.src_stage_mask);
+            let dst_stage_mask = stage_flags2(barrier.dst_stage_mask);
+            let src_access_mask = access_flags2(barrier.src_access_mask);
+            let dst_access_mask = access_flags2(barrier.dst_access_mask);
+            match barrier.kind {
+                BarrierKind::Global => memory_barriers.push(
+                    vk::MemoryBarrier2KHR::builder()
+                        .src_stage_mask(src_stage_mask)
+                        .src_access_mask(src_access_mask)
+                        .dst_stage_mask(dst_stage_mask)
+                        .dst_access_mask(dst_access_mask)
+                        .build(),
+                ),
+                BarrierKind::Buffer {
+                    buffer,
+                    offset,
+                    size,
+                } => buffer_barriers.push(
+                    vk::BufferMemoryBarrier2KHR::builder()
+                        .src_stage_mask(src_stage_mask)
+                        .src_access_mask(src_access_mask)
+                        .dst_stage_mask(dst_stage_mask)
+                        .dst_access_mask(dst_access_mask)
+                        .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                        .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                        .buffer(buffer)
+                        .offset(offset)
+                        .size(size)
+                        .build(),
+                ),
+                BarrierKind::Image {
+                    image,
+                    range,
+                    old_layout,
+                    new_layout,
+                } => image_barriers.push(
+                    vk::ImageMemoryBarrier2KHR::builder()
+                        .src_stage_mask(src_stage_mask)
+                        .src_access_mask(src_access_mask)
+                        .dst_stage_mask(dst_stage_mask)
+                        .dst_access_mask(dst_access_mask)
+                        .old_layout(old_layout)
+                        .new_layout(new_layout)
+                        .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                        .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                        .image(image)
+                        .subresource_range(range)
+                        .build(),
+                ),
+            }
+        }
+        let dependency_info = vk::DependencyInfoKHR::builder()
+            .memory_barriers(&memory_barriers)
+            .buffer_memory_barriers(&buffer_barriers)
+            .image_memory_barriers(&image_barriers);
+        synchronization2.cmd_pipeline_barrier2(command_buffer, &dependency_info);
+    }
+}
+
+/// Resolves [`vk::REMAINING_MIP_LEVELS`] and [`vk::REMAINING_ARRAY_LAYERS`] in `range`.
+fn resolve_range(
+    state: &ImageState,
+    range: &vk::ImageSubresourceRange,
+) -> (std::ops::Range<u32>, std::ops::Range<u32>) {
+    let level_end = if range.level_count == vk::REMAINING_MIP_LEVELS {
+        state.mip_levels
+    } else {
+        range.base_mip_level + range.level_count
+    };
+    let layer_end = if range.layer_count == vk::REMAINING_ARRAY_LAYERS {
+        state.array_layers
+    } else {
+        range.base_array_layer + range.layer_count
+    };
+    (
+        range.base_mip_level..level_end,
+        range.base_array_layer..layer_end,
+    )
+}
+
+fn image_barrier(
+    image: vk::Image,
+    range: &vk::ImageSubresourceRange,
+    mip_level: u32,
+    layers: std::ops::Range<u32>,
+    old: &State,
+    (src_stage_mask, src_access_mask): Source,
+    next: &AccessInfo,
+) -> PendingBarrier {
+    let new_layout = if next.image_layout == vk::ImageLayout::UNDEFINED {
+        old.layout
+    } else {
+        next.image_layout
+    };
+    PendingBarrier {
+        src_stage_mask,
+        src_access_mask,
+        dst_stage_mask: next.stage_mask,
+        dst_access_mask: next.access_mask,
+        kind: BarrierKind::Image {
+            image,
+            range: vk::ImageSubresourceRange {
+                aspect_mask: range.aspect_mask,
+                base_mip_level: mip_level,
+                level_count: 1,
+                base_array_layer: layers.start,
+                layer_count: layers.end - layers.start,
+            },
+            old_layout: old.layout,
+            new_layout,
+        },
+    }
+}
+
+/// Extends `prev` to cover the mip levels of `next` if both are otherwise identical image
+/// barriers on consecutive mip levels.
+fn extend_mip_levels(prev: &mut PendingBarrier, next: &PendingBarrier) -> bool {
+    if prev.src_stage_mask != next.src_stage_mask || prev.src_access_mask != next.src_access_mask {
+        return false;
+    }
+    match (&mut prev.kind, &next.kind) {
+        (
+            BarrierKind::Image {
+                range: prev_range,
+                old_layout: prev_old,
+                new_layout: prev_new,
+                ..
+            },
+            BarrierKind::Image {
+                range,
+                old_layout,
+                new_layout,
+                ..
+            },
+        ) if prev_old == old_layout
+            && prev_new == new_layout
+            && prev_range.base_array_layer == range.base_array_layer
+            && prev_range.layer_count == range.layer_count
+            && prev_range.base_mip_level + prev_range.level_count == range.base_mip_level =>
+        {
+            prev_range.level_count += range.level_count;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Merges image barriers starting at `first` that only differ in consecutive mip levels.
+fn merge_mip_levels(pending: &mut Vec<PendingBarrier>, first: usize) {
+    let mut merged: Vec<PendingBarrier> = Vec::with_capacity(pending.len() - first);
+    for barrier in pending.drain(first..) {
+        if !merged
+            .iter_mut()
+            .any(|prev| extend_mip_levels(prev, &barrier))
+        {
+            merged.push(barrier);
+        }
+    }
+    pending.extend(merged);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vk::Handle;
+
+    fn color_range() -> vk::ImageSubresourceRange {
+        vk::ImageSubresourceRange {
+            aspect_mask: vk::ImageAspectFlags::COLOR,
+            base_mip_level: 0,
+            level_count: vk::REMAINING_MIP_LEVELS,
+            base_array_layer: 0,
+            layer_count: vk::REMAINING_ARRAY_LAYERS,
+        }
+    }
+
+    #[test]
+    fn image_transitions_are_merged() {
+        let image = vk::Image::from_raw(1);
+        let mut tracker = ResourceTracker::new();
+        tracker.register_image(image, 4, 2, vk::ImageLayout::UNDEFINED);
+        tracker.use_image(image, color_range(), &[AccessType::TransferWrite]);
+        assert_eq!(tracker.pending.len(), 1);
+        match tracker.pending[0].kind {
+            BarrierKind::Image {
+                range,
+                old_layout,
+                new_layout,
+                ..
+            } => {
+                assert_eq!((range.level_count, range.layer_count), (4, 2));
+                assert!(old_layout == vk::ImageLayout::UNDEFINED);
+                assert!(new_layout == vk::ImageLayout::TRANSFER_DST_OPTIMAL);
+            }
+            _ => panic!("expected an image barrier"),
+        }
+        tracker.pending.clear();
+
+        let sampled = [AccessType::FragmentShaderReadSampledImageOrUniformTexelBuffer];
+        tracker.use_image(image, color_range(), &sampled);
+        assert_eq!(tracker.pending.len(), 1);
+        assert!(tracker.pending[0].src_access_mask == vk::AccessFlags::TRANSFER_WRITE);
+        tracker.pending.clear();
+
+        // Reading again in the same layout needs no barrier.
+        tracker.use_image(image, color_range(), &sampled);
+        assert!(!tracker.has_pending_barriers());
+        assert!(
+            tracker.image_layout(image, 3, 1) == Some(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+        );
+    }
+
+    #[test]
+    fn buffer_ranges_are_tracked_separately() {
+        let buffer = vk::Buffer::from_raw(1);
+        let mut tracker = ResourceTracker::new();
+        tracker.use_buffer(buffer, 0, 256, &[AccessType::TransferWrite]);
+        // Nothing was accessed before the first write.
+        assert!(!tracker.has_pending_barriers());
+
+        tracker.use_buffer(buffer, 256, 256, &[AccessType::VertexBuffer]);
+        assert!(!tracker.has_pending_barriers());
+
+        tracker.use_buffer(buffer, 0, 512, &[AccessType::VertexBuffer]);
+        assert_eq!(tracker.pending.len(), 1);
+        assert!(tracker.pending[0].src_access_mask == vk::AccessFlags::TRANSFER_WRITE);
+        tracker.pending.clear();
+
+        // Write-after-read only needs an execution dependency.
+        tracker.use_buffer(buffer, 256, 256, &[AccessType::ComputeShaderWrite]);
+        assert_eq!(tracker.pending.len(), 1);
+        assert!(matches!(tracker.pending[0].kind, BarrierKind::Global));
+        assert!(tracker.pending[0].src_stage_mask == vk::PipelineStageFlags::VERTEX_INPUT);
+    }
+}
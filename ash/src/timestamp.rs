@@ -0,0 +1,281 @@
+//! Helpers for GPU timestamp queries.
+//!
+//! [`TimestampCalibration`] correlates the device timestamp counter with `CLOCK_MONOTONIC`
+//! through `VK_EXT_calibrated_timestamps`, so that the results of
+//! [`Device::cmd_write_timestamp()`] can be placed on the same timeline as host-side traces.
+//! [`TimestampScopes`] measures the duration of command buffer regions.
+
+use crate::extensions::ext;
+use crate::prelude::*;
+use crate::query;
+use crate::vk;
+use crate::Device;
+
+/// Mask of the bits of a timestamp that are written by the device.
+fn valid_mask(timestamp_valid_bits: u32) -> u64 {
+    match timestamp_valid_bits {
+        0 | 64.. => u64::MAX,
+        bits => (1 << bits) - 1,
+    }
+}
+
+/// A pair of device and `CLOCK_MONOTONIC` timestamps taken at the same moment, used to convert
+/// device ticks to host nanoseconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimestampCalibration {
+    device_ticks: u64,
+    monotonic_ns: u64,
+    max_deviation_ns: u64,
+    timestamp_period: f64,
+    valid_mask: u64,
+}
+
+impl TimestampCalibration {
+    /// `timestamp_period` is [`vk::PhysicalDeviceLimits::timestamp_period`] and
+    /// `timestamp_valid_bits` is [`vk::QueueFamilyProperties::timestamp_valid_bits`] of the queue
+    /// family the timestamps are written on.
+    pub fn new(
+        device_ticks: u64,
+        monotonic_ns: u64,
+        max_deviation_ns: u64,
+        timestamp_period: f32,
+        timestamp_valid_bits: u32,
+    ) -> Self {
+        let valid_mask = valid_mask(timestamp_valid_bits);
+        Self {
+            device_ticks: device_ticks & valid_mask,
+            monotonic_ns,
+            max_deviation_ns,
+            timestamp_period: timestamp_period.into(),
+            valid_mask,
+        }
+    }
+
+    /// Samples the [`vk::TimeDomainEXT::DEVICE`] and [`vk::TimeDomainEXT::CLOCK_MONOTONIC`]
+    /// time domains, which must both be reported by
+    /// [`ext::CalibratedTimestamps::get_physical_device_calibrateable_time_domains()`].
+    ///
+    /// The device clock drifts relative to the host clock, so long-running applications should
+    /// recalibrate periodically.
+    pub unsafe fn calibrate(
+        calibrated_timestamps: &ext::CalibratedTimestamps,
+        timestamp_period: f32,
+        timestamp_valid_bits: u32,
+    ) -> VkResult<Self> {
+        let infos = [
+            vk::CalibratedTimestampInfoEXT::builder()
+                .time_domain(vk::TimeDomainEXT::DEVICE)
+                .build(),
+            vk::CalibratedTimestampInfoEXT::builder()
+                .time_domain(vk::TimeDomainEXT::CLOCK_MONOTONIC)
+                .build(),
+        ];
+        let (timestamps, max_deviation_ns) =
+            calibrated_timestamps.get_calibrated_timestamps(&infos)?;
+        Ok(Self::new(
+            timestamps[0],
+            timestamps[1],
+            max_deviation_ns,
+            timestamp_period,
+            timestamp_valid_bits,
+        ))
+    }
+
+    /// Maximum deviation between the two sampled timestamps, in nanoseconds.
+    pub fn max_deviation_ns(&self) -> u64 {
+        self.max_deviation_ns
+    }
+
+    /// Converts a device timestamp to `CLOCK_MONOTONIC` nanoseconds.
+    ///
+    /// Timestamps up to half the range of the valid timestamp bits before or after the
+    /// calibration point are converted correctly across counter wrap-around.
+    pub fn to_monotonic_ns(&self, device_ticks: u64) -> u64 {
+        let forward = device_ticks.wrapping_sub(self.device_ticks) & self.valid_mask;
+        if forward <= self.valid_mask / 2 {
+            let ns = (forward as f64 * self.timestamp_period).round() as u64;
+            self.monotonic_ns.saturating_add(ns)
+        } else {
+            let backward = self.device_ticks.wrapping_sub(device_ticks) & self.valid_mask;
+            let ns = (backward as f64 * self.timestamp_period).round() as u64;
+            self.monotonic_ns.saturating_sub(ns)
+        }
+    }
+
+    /// Reads `query_count` timestamp queries with [`query::get_timestamp_query_results()`] and
+    /// converts them to `CLOCK_MONOTONIC` nanoseconds, or [`None`] for queries that are not
+    /// available yet.
+    pub unsafe fn get_query_pool_results(
+        &self,
+        device: &Device,
+        query_pool: vk::QueryPool,
+        first_query: u32,
+        query_count: u32,
+        wait: bool,
+    ) -> VkResult<Vec<Option<u64>>> {
+        let timestamps =
+            query::get_timestamp_query_results(device, query_pool, first_query, query_count, wait)?;
+        Ok(timestamps
+            .into_iter()
+            .map(|timestamp| timestamp.map(|ticks| self.to_monotonic_ns(ticks)))
+            .collect())
+    }
+}
+
+/// A region of a command buffer timed by [`TimestampScopes`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TimestampScope {
+    index: u32,
+}
+
+impl TimestampScope {
+    /// Position of the scope in the results of [`TimestampScopes::get_durations_ns()`].
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+}
+
+/// A timestamp query pool that hands out pairs of queries to time regions of command buffers.
+///
+/// ```no_run
+/// # use ash::{timestamp::TimestampScopes, vk};
+/// # unsafe fn record(device: &ash::Device, command_buffer: vk::CommandBuffer, scopes: &mut TimestampScopes) {
+/// scopes.reset(device, command_buffer);
+/// let scope = scopes
+///     .begin(device, command_buffer, vk::PipelineStageFlags::TOP_OF_PIPE)
+///     .unwrap();
+/// // Record the work to time.
+/// scopes.end(device, command_buffer, scope, vk::PipelineStageFlags::BOTTOM_OF_PIPE);
+/// # }
+/// ```
+///
+/// Once the command buffer has executed, [`TimestampScopes::get_durations_ns()`] returns the
+/// duration of every scope. The pool is not destroyed on [`Drop`]; call
+/// [`TimestampScopes::destroy()`] once it is no longer needed.
+#[derive(Debug)]
+pub struct TimestampScopes {
+    query_pool: vk::QueryPool,
+    capacity: u32,
+    len: u32,
+    timestamp_period: f64,
+    valid_mask: u64,
+}
+
+impl TimestampScopes {
+    /// Creates a query pool for `capacity` scopes. `timestamp_period` and
+    /// `timestamp_valid_bits` have the same meaning as for [`TimestampCalibration::new()`].
+    pub unsafe fn new(
+        device: &Device,
+        capacity: u32,
+        timestamp_period: f32,
+        timestamp_valid_bits: u32,
+    ) -> VkResult<Self> {
+        let create_info = vk::QueryPoolCreateInfo::builder()
+            .query_type(vk::QueryType::TIMESTAMP)
+            .query_count(capacity * 2);
+        let query_pool = device.create_query_pool(&create_info, None)?;
+        Ok(Self {
+            query_pool,
+            capacity,
+            len: 0,
+            timestamp_period: timestamp_period.into(),
+            valid_mask: valid_mask(timestamp_valid_bits),
+        })
+    }
+
+    pub fn query_pool(&self) -> vk::QueryPool {
+        self.query_pool
+    }
+
+    /// Number of scopes begun since the last [`TimestampScopes::reset()`].
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Records a reset of all queries and starts handing out scopes from the beginning.
+    pub unsafe fn reset(&mut self, device: &Device, command_buffer: vk::CommandBuffer) {
+        device.cmd_reset_query_pool(command_buffer, self.query_pool, 0, self.capacity * 2);
+        self.len = 0;
+    }
+
+    /// Writes the start timestamp of a new scope once all previous commands have reached
+    /// `stage`. Returns [`None`] when all scopes are in use.
+    pub unsafe fn begin(
+        &mut self,
+        device: &Device,
+        command_buffer: vk::CommandBuffer,
+        stage: vk::PipelineStageFlags,
+    ) -> Option<TimestampScope> {
+        if self.len == self.capacity {
+            return None;
+        }
+        let scope = TimestampScope { index: self.len };
+        self.len += 1;
+        device.cmd_write_timestamp(command_buffer, stage, self.query_pool, scope.index * 2);
+        Some(scope)
+    }
+
+    /// Writes the end timestamp of `scope` once all previous commands have reached `stage`.
+    pub unsafe fn end(
+        &self,
+        device: &Device,
+        command_buffer: vk::CommandBuffer,
+        scope: TimestampScope,
+        stage: vk::PipelineStageFlags,
+    ) {
+        device.cmd_write_timestamp(command_buffer, stage, self.query_pool, scope.index * 2 + 1);
+    }
+
+    /// Returns the duration in nanoseconds of every scope begun since the last reset, or
+    /// [`None`] for scopes whose timestamps are not available yet.
+    pub unsafe fn get_durations_ns(
+        &self,
+        device: &Device,
+        wait: bool,
+    ) -> VkResult<Vec<Option<f64>>> {
+        if self.len == 0 {
+            return Ok(Vec::new());
+        }
+        let timestamps =
+            query::get_timestamp_query_results(device, self.query_pool, 0, self.len * 2, wait)?;
+        Ok(timestamps
+            .chunks_exact(2)
+            .map(|scope| match *scope {
+                [Some(begin), Some(end)] => {
+                    let ticks = end.wrapping_sub(begin) & self.valid_mask;
+                    Some(ticks as f64 * self.timestamp_period)
+                }
+                _ => None,
+            })
+            .collect())
+    }
+
+    pub unsafe fn destroy(self, device: &Device) {
+        device.destroy_query_pool(self.query_pool, None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticks_to_monotonic() {
+        let calibration = TimestampCalibration::new(1_000, 5_000_000, 10, 2.5, 64);
+        assert_eq!(calibration.to_monotonic_ns(1_000), 5_000_000);
+        assert_eq!(calibration.to_monotonic_ns(1_400), 5_001_000);
+        assert_eq!(calibration.to_monotonic_ns(600), 4_999_000);
+    }
+
+    #[test]
+    fn ticks_wrap_around() {
+        let max = (1 << 36) - 1;
+        let calibration = TimestampCalibration::new(max - 9, 1_000_000, 0, 1.0, 36);
+        assert_eq!(calibration.to_monotonic_ns(10), 1_000_020);
+        assert_eq!(calibration.to_monotonic_ns(max - 19), 999_990);
+    }
+}
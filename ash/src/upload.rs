@@ -0,0 +1,924 @@
+//! Batched host-to-device uploads and device-to-host readbacks through a ring staging buffer.
+//!
+//! [`Uploader`] copies data into a persistently mapped staging buffer allocated through
+//! [`Allocator`], records the copies together with the layout transitions they need into a
+//! command buffer, and submits them to a (typically dedicated transfer) queue on
+//! [`Uploader::submit()`]. Every submission is identified by a monotonically increasing value,
+//! which doubles as the timeline semaphore value when the uploader is created with
+//! [`UploaderCreateDesc::timeline_semaphore`].
+
+use crate::alloc::{Allocation, Allocator, MemoryUsage};
+use crate::prelude::*;
+use crate::vk;
+use crate::Device;
+use std::collections::{HashMap, VecDeque};
+use std::ptr;
+
+/// Staging offsets are aligned to this many bytes, which satisfies the copy offset requirements
+/// of every format whose texel block size is a power of two.
+const COPY_ALIGNMENT: vk::DeviceSize = 16;
+
+fn align_up(offset: vk::DeviceSize, alignment: vk::DeviceSize) -> vk::DeviceSize {
+    offset + (alignment - offset % alignment) % alignment
+}
+
+/// Byte ranges of the staging buffer, handed out in submission order and released in the same
+/// order once their batch retires.
+struct StagingRing {
+    capacity: vk::DeviceSize,
+    head: vk::DeviceSize,
+    tail: vk::DeviceSize,
+}
+
+impl StagingRing {
+    fn new(capacity: vk::DeviceSize) -> Self {
+        Self {
+            capacity,
+            head: 0,
+            tail: 0,
+        }
+    }
+
+    /// Returns [`vk::Result::ERROR_OUT_OF_DEVICE_MEMORY`] when `size` bytes can never fit, even
+    /// in an empty ring.
+    fn check_size(&self, size: vk::DeviceSize) -> VkResult<()> {
+        if size + COPY_ALIGNMENT > self.capacity {
+            Err(vk::Result::ERROR_OUT_OF_DEVICE_MEMORY)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Tries to reserve `size` bytes without waiting.
+    fn try_reserve(&mut self, size: vk::DeviceSize) -> Option<vk::DeviceSize> {
+        // Ranges are only wrapped to the start when they would end strictly before the tail, so
+        // `head == tail` always means the ring is empty.
+        if self.head >= self.tail {
+            let start = align_up(self.head, COPY_ALIGNMENT);
+            if start + size <= self.capacity {
+                self.head = start + size;
+                return Some(start);
+            }
+            if size < self.tail {
+                self.head = size;
+                return Some(0);
+            }
+        } else {
+            let start = align_up(self.head, COPY_ALIGNMENT);
+            if start + size < self.tail {
+                self.head = start + size;
+                return Some(start);
+            }
+        }
+        None
+    }
+
+    /// End of the most recent reservation.
+    fn head(&self) -> vk::DeviceSize {
+        self.head
+    }
+
+    /// Releases everything reserved up to `ring_end`, a previous [`StagingRing::head()`].
+    fn release(&mut self, ring_end: vk::DeviceSize) {
+        self.tail = ring_end;
+    }
+
+    fn reset(&mut self) {
+        self.head = 0;
+        self.tail = 0;
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct UploaderCreateDesc {
+    /// Queue that copies are submitted to.
+    pub queue: vk::Queue,
+    pub queue_family_index: u32,
+    /// Size of the ring staging buffer. Single transfers may not exceed this size.
+    pub staging_size: vk::DeviceSize,
+    /// Track completion with a timeline semaphore instead of fences. Requires the
+    /// `timelineSemaphore` feature of Vulkan 1.2.
+    pub timeline_semaphore: bool,
+}
+
+/// Destination of an image upload, or source of an image readback.
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct ImageTransfer {
+    pub image: vk::Image,
+    pub subresource: vk::ImageSubresourceLayers,
+    pub offset: vk::Offset3D,
+    pub extent: vk::Extent3D,
+    /// Layout of the image before the transfer. May be [`vk::ImageLayout::UNDEFINED`] for
+    /// uploads that overwrite the whole subresource.
+    pub old_layout: vk::ImageLayout,
+    /// Layout the image is left in after the transfer.
+    pub new_layout: vk::ImageLayout,
+    /// Queue family that uses the image after the transfer, if it differs from the uploader's.
+    /// The matching acquire barrier is returned by [`Uploader::image_acquire_barrier()`].
+    pub dst_queue_family_index: Option<u32>,
+}
+
+impl ImageTransfer {
+    fn subresource_range(&self) -> vk::ImageSubresourceRange {
+        vk::ImageSubresourceRange {
+            aspect_mask: self.subresource.aspect_mask,
+            base_mip_level: self.subresource.mip_level,
+            level_count: 1,
+            base_array_layer: self.subresource.base_array_layer,
+            layer_count: self.subresource.layer_count,
+        }
+    }
+
+    fn copy_region(&self, buffer_offset: vk::DeviceSize) -> vk::BufferImageCopy {
+        vk::BufferImageCopy {
+            buffer_offset,
+            buffer_row_length: 0,
+            buffer_image_height: 0,
+            image_subresource: self.subresource,
+            image_offset: self.offset,
+            image_extent: self.extent,
+        }
+    }
+}
+
+/// Identifies a call to [`Uploader::submit()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UploadSubmission {
+    /// Timeline semaphore that reaches [`Self::value`] once the submission has completed, or
+    /// [`vk::Semaphore::null()`] when the uploader tracks completion with fences.
+    pub semaphore: vk::Semaphore,
+    /// Fence signaled by the submission, or [`vk::Fence::null()`] in timeline mode. The fence is
+    /// recycled once the uploader has observed it signaled.
+    pub fence: vk::Fence,
+    pub value: u64,
+}
+
+/// Handle to data that is read back by a pending submission, see [`Uploader::take_readback()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Readback {
+    id: u64,
+    value: u64,
+}
+
+impl Readback {
+    /// Value of the submission that performs the copy.
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+}
+
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+enum PendingCopy {
+    BufferUpload {
+        staging_offset: vk::DeviceSize,
+        dst: vk::Buffer,
+        dst_offset: vk::DeviceSize,
+        size: vk::DeviceSize,
+        dst_queue_family_index: Option<u32>,
+    },
+    ImageUpload {
+        staging_offset: vk::DeviceSize,
+        transfer: ImageTransfer,
+    },
+    BufferReadback {
+        staging_offset: vk::DeviceSize,
+        src: vk::Buffer,
+        src_offset: vk::DeviceSize,
+        size: vk::DeviceSize,
+    },
+    ImageReadback {
+        staging_offset: vk::DeviceSize,
+        transfer: ImageTransfer,
+    },
+}
+
+#[derive(Debug)]
+struct PendingReadback {
+    id: u64,
+    staging_offset: vk::DeviceSize,
+    size: vk::DeviceSize,
+}
+
+#[derive(Debug)]
+struct Batch {
+    value: u64,
+    command_buffer: vk::CommandBuffer,
+    fence: vk::Fence,
+    /// End of the ring range used by this batch; the ring tail moves here once it retires.
+    ring_end: vk::DeviceSize,
+    readbacks: Vec<PendingReadback>,
+}
+
+/// Batches staging copies onto a transfer queue.
+///
+/// The uploader does not implement [`Drop`]; call [`Uploader::destroy()`] once it is no longer
+/// needed.
+pub struct Uploader {
+    device: Device,
+    queue: vk::Queue,
+    queue_family_index: u32,
+    command_pool: vk::CommandPool,
+    timeline: vk::Semaphore,
+
+    staging_buffer: vk::Buffer,
+    staging: Allocation,
+    ring: StagingRing,
+
+    pending: Vec<PendingCopy>,
+    pending_readbacks: Vec<PendingReadback>,
+    in_flight: VecDeque<Batch>,
+    free_command_buffers: Vec<vk::CommandBuffer>,
+    free_fences: Vec<vk::Fence>,
+    completed_readbacks: HashMap<u64, Vec<u8>>,
+
+    next_value: u64,
+    completed_value: u64,
+    next_readback_id: u64,
+}
+
+unsafe impl Send for Uploader {}
+
+impl Uploader {
+    pub unsafe fn new(allocator: &mut Allocator, desc: &UploaderCreateDesc) -> VkResult<Self> {
+        let device = allocator.device().clone();
+
+        let buffer_info = vk::BufferCreateInfo::builder()
+            .size(desc.staging_size)
+            .usage(vk::BufferUsageFlags::TRANSFER_SRC | vk::BufferUsageFlags::TRANSFER_DST)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE);
+        let staging_buffer = device.create_buffer(&buffer_info, None)?;
+        let staging = match allocator.allocate_for_buffer(staging_buffer, MemoryUsage::CpuOnly) {
+            Ok(staging) => staging,
+            Err(err) => {
+                device.destroy_buffer(staging_buffer, None);
+                return Err(err);
+            }
+        };
+
+        let pool_info = vk::CommandPoolCreateInfo::builder()
+            .flags(
+                vk::CommandPoolCreateFlags::TRANSIENT
+                    | vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER,
+            )
+            .queue_family_index(desc.queue_family_index);
+        let command_pool = match device.create_command_pool(&pool_info, None) {
+            Ok(command_pool) => command_pool,
+            Err(err) => {
+                device.destroy_buffer(staging_buffer, None);
+                allocator.free(staging);
+                return Err(err);
+            }
+        };
+
+        let timeline = if desc.timeline_semaphore {
+            let mut type_info = vk::SemaphoreTypeCreateInfo::builder()
+                .semaphore_type(vk::SemaphoreType::TIMELINE)
+                .initial_value(0);
+            let semaphore_info = vk::SemaphoreCreateInfo::builder().push_next(&mut type_info);
+            match device.create_semaphore(&semaphore_info, None) {
+                Ok(timeline) => timeline,
+                Err(err) => {
+                    device.destroy_command_pool(command_pool, None);
+                    device.destroy_buffer(staging_buffer, None);
+                    allocator.free(staging);
+                    return Err(err);
+                }
+            }
+        } else {
+            vk::Semaphore::null()
+        };
+
+        Ok(Self {
+            device,
+            queue: desc.queue,
+            queue_family_index: desc.queue_family_index,
+            command_pool,
+            timeline,
+            staging_buffer,
+            staging,
+            ring: StagingRing::new(desc.staging_size),
+            pending: Vec::new(),
+            pending_readbacks: Vec::new(),
+            in_flight: VecDeque::new(),
+            free_command_buffers: Vec::new(),
+            free_fences: Vec::new(),
+            completed_readbacks: HashMap::new(),
+            next_value: 1,
+            completed_value: 0,
+            next_readback_id: 0,
+        })
+    }
+
+    pub fn queue_family_index(&self) -> u32 {
+        self.queue_family_index
+    }
+
+    /// Timeline semaphore signaled by submissions, or [`vk::Semaphore::null()`] in fence mode.
+    pub fn timeline_semaphore(&self) -> vk::Semaphore {
+        self.timeline
+    }
+
+    /// Reserves `size` bytes of the ring, submitting pending copies and waiting for in-flight
+    /// submissions as needed.
+    unsafe fn reserve(&mut self, size: vk::DeviceSize) -> VkResult<vk::DeviceSize> {
+        self.ring.check_size(size)?;
+        loop {
+            self.retire()?;
+            if let Some(offset) = self.ring.try_reserve(size) {
+                return Ok(offset);
+            }
+            if let Some(oldest) = self.in_flight.front() {
+                let value = oldest.value;
+                self.wait_value(value, u64::MAX)?;
+            } else if !self.pending.is_empty() {
+                self.submit()?;
+            } else {
+                // Nothing holds on to the ring anymore.
+                self.ring.reset();
+            }
+        }
+    }
+
+    unsafe fn write_staging(&mut self, offset: vk::DeviceSize, data: &[u8]) {
+        let mapped = self
+            .staging
+            .mapped_ptr()
+            .expect("staging memory is not host-visible");
+        ptr::copy_nonoverlapping(
+            data.as_ptr(),
+            (mapped.as_ptr() as *mut u8).add(offset as usize),
+            data.len(),
+        );
+    }
+
+    /// Copies `data` to `dst` at `dst_offset`. The buffer must not be in use by the device.
+    /// Empty `data` records nothing.
+    pub unsafe fn upload_buffer(
+        &mut self,
+        data: &[u8],
+        dst: vk::Buffer,
+        dst_offset: vk::DeviceSize,
+        dst_queue_family_index: Option<u32>,
+    ) -> VkResult<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let size = data.len() as vk::DeviceSize;
+        let staging_offset = self.reserve(size)?;
+        self.write_staging(staging_offset, data);
+        self.pending.push(PendingCopy::BufferUpload {
+            staging_offset,
+            dst,
+            dst_offset,
+            size,
+            dst_queue_family_index,
+        });
+        Ok(())
+    }
+
+    /// Copies tightly packed texel data into a single subresource region of an image.
+    /// Empty `data` records nothing, not even the layout transitions.
+    pub unsafe fn upload_image(&mut self, data: &[u8], transfer: &ImageTransfer) -> VkResult<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let staging_offset = self.reserve(data.len() as vk::DeviceSize)?;
+        self.write_staging(staging_offset, data);
+        self.pending.push(PendingCopy::ImageUpload {
+            staging_offset,
+            transfer: *transfer,
+        });
+        Ok(())
+    }
+
+    /// Reads `size` bytes from `src` at `src_offset` once the next submission completes.
+    pub unsafe fn readback_buffer(
+        &mut self,
+        src: vk::Buffer,
+        src_offset: vk::DeviceSize,
+        size: vk::DeviceSize,
+    ) -> VkResult<Readback> {
+        let staging_offset = self.reserve(size)?;
+        self.pending.push(PendingCopy::BufferReadback {
+            staging_offset,
+            src,
+            src_offset,
+            size,
+        });
+        Ok(self.push_readback(staging_offset, size))
+    }
+
+    /// Reads `size` bytes of tightly packed texel data from an image region once the next
+    /// submission completes. [`ImageTransfer::old_layout`] must be the current layout of the
+    /// image.
+    pub unsafe fn readback_image(
+        &mut self,
+        transfer: &ImageTransfer,
+        size: vk::DeviceSize,
+    ) -> VkResult<Readback> {
+        let staging_offset = self.reserve(size)?;
+        self.pending.push(PendingCopy::ImageReadback {
+            staging_offset,
+            transfer: *transfer,
+        });
+        Ok(self.push_readback(staging_offset, size))
+    }
+
+    fn push_readback(&mut self, staging_offset: vk::DeviceSize, size: vk::DeviceSize) -> Readback {
+        let id = self.next_readback_id;
+        self.next_readback_id += 1;
+        self.pending_readbacks.push(PendingReadback {
+            id,
+            staging_offset,
+            size,
+        });
+        Readback {
+            id,
+            value: self.next_value,
+        }
+    }
+
+    /// Returns the data of a completed readback, or [`None`] if its submission has not finished
+    /// yet. Each readback can be taken once.
+    pub unsafe fn take_readback(&mut self, readback: &Readback) -> VkResult<Option<Vec<u8>>> {
+        self.retire()?;
+        Ok(self.completed_readbacks.remove(&readback.id))
+    }
+
+    /// Release barrier half of a queue family ownership transfer; the acquire half is recorded
+    /// by the user on the destination queue.
+    fn ownership(&self, dst_queue_family_index: Option<u32>) -> (u32, u32) {
+        match dst_queue_family_index {
+            Some(dst) if dst != self.queue_family_index => (self.queue_family_index, dst),
+            _ => (vk::QUEUE_FAMILY_IGNORED, vk::QUEUE_FAMILY_IGNORED),
+        }
+    }
+
+    /// Barrier acquiring ownership of an uploaded image on [`ImageTransfer::dst_queue_family_index`],
+    /// to be recorded on that queue after waiting for the upload's submission.
+    pub fn image_acquire_barrier(
+        &self,
+        transfer: &ImageTransfer,
+        dst_access_mask: vk::AccessFlags,
+    ) -> vk::ImageMemoryBarrier {
+        let (src, dst) = self.ownership(transfer.dst_queue_family_index);
+        vk::ImageMemoryBarrier::builder()
+            .dst_access_mask(dst_access_mask)
+            .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+            .new_layout(transfer.new_layout)
+            .src_queue_family_index(src)
+            .dst_queue_family_index(dst)
+            .image(transfer.image)
+            .subresource_range(transfer.subresource_range())
+            .build()
+    }
+
+    /// Barrier acquiring ownership of an uploaded buffer range on `dst_queue_family_index`, to
+    /// be recorded on that queue after waiting for the upload's submission.
+    pub fn buffer_acquire_barrier(
+        &self,
+        buffer: vk::Buffer,
+        offset: vk::DeviceSize,
+        size: vk::DeviceSize,
+        dst_queue_family_index: u32,
+        dst_access_mask: vk::AccessFlags,
+    ) -> vk::BufferMemoryBarrier {
+        let (src, dst) = self.ownership(Some(dst_queue_family_index));
+        vk::BufferMemoryBarrier::builder()
+            .dst_access_mask(dst_access_mask)
+            .src_queue_family_index(src)
+            .dst_queue_family_index(dst)
+            .buffer(buffer)
+            .offset(offset)
+            .size(size)
+            .build()
+    }
+
+    unsafe fn record(&self, command_buffer: vk::CommandBuffer) {
+        let mut pre_images = Vec::new();
+        let mut post_images = Vec::new();
+        let mut post_buffers = Vec::new();
+        let mut has_uploads = false;
+        let mut has_readbacks = false;
+
+        for copy in &self.pending {
+            match *copy {
+                PendingCopy::BufferUpload {
+                    dst,
+                    dst_offset,
+                    size,
+                    dst_queue_family_index,
+                    ..
+                } => {
+                    has_uploads = true;
+                    let (src_family, dst_family) = self.ownership(dst_queue_family_index);
+                    if src_family != dst_family {
+                        post_buffers.push(
+                            vk::BufferMemoryBarrier::builder()
+                                .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+                                .src_queue_family_index(src_family)
+                                .dst_queue_family_index(dst_family)
+                                .buffer(dst)
+                                .offset(dst_offset)
+                                .size(size)
+                                .build(),
+                        );
+                    }
+                }
+                PendingCopy::ImageUpload { transfer, .. } => {
+                    has_uploads = true;
+                    // Earlier device writes to the image have to be made available before the
+                    // layout transition and the copy.
+                    let src_access_mask = if transfer.old_layout == vk::ImageLayout::UNDEFINED {
+                        vk::AccessFlags::empty()
+                    } else {
+                        vk::AccessFlags::MEMORY_WRITE
+                    };
+                    pre_images.push(
+                        vk::ImageMemoryBarrier::builder()
+                            .src_access_mask(src_access_mask)
+                            .dst_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+                            .old_layout(transfer.old_layout)
+                            .new_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+                            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                            .image(transfer.image)
+                            .subresource_range(transfer.subresource_range())
+                            .build(),
+                    );
+                    let (src_family, dst_family) = self.ownership(transfer.dst_queue_family_index);
+                    post_images.push(
+                        vk::ImageMemoryBarrier::builder()
+                            .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+                            .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+                            .new_layout(transfer.new_layout)
+                            .src_queue_family_index(src_family)
+                            .dst_queue_family_index(dst_family)
+                            .image(transfer.image)
+                            .subresource_range(transfer.subresource_range())
+                            .build(),
+                    );
+                }
+                PendingCopy::BufferReadback { .. } => has_readbacks = true,
+                PendingCopy::ImageReadback { transfer, .. } => {
+                    has_readbacks = true;
+                    pre_images.push(
+                        vk::ImageMemoryBarrier::builder()
+                            .src_access_mask(vk::AccessFlags::MEMORY_WRITE)
+                            .dst_access_mask(vk::AccessFlags::TRANSFER_READ)
+                            .old_layout(transfer.old_layout)
+                            .new_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+                            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                            .image(transfer.image)
+                            .subresource_range(transfer.subresource_range())
+                            .build(),
+                    );
+                    post_images.push(
+                        vk::ImageMemoryBarrier::builder()
+                            .src_access_mask(vk::AccessFlags::TRANSFER_READ)
+                            .old_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+                            .new_layout(transfer.new_layout)
+                            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                            .image(transfer.image)
+                            .subresource_range(transfer.subresource_range())
+                            .build(),
+                    );
+                }
+            }
+        }
+
+        if !pre_images.is_empty() || has_readbacks {
+            // Readbacks wait for all prior device writes to the source resources.
+            let memory_barrier = vk::MemoryBarrier::builder()
+                .src_access_mask(vk::AccessFlags::MEMORY_WRITE)
+                .dst_access_mask(vk::AccessFlags::TRANSFER_READ)
+                .build();
+            let memory_barriers: &[vk::MemoryBarrier] = if has_readbacks {
+                std::slice::from_ref(&memory_barrier)
+            } else {
+                &[]
+            };
+            self.device.cmd_pipeline_barrier(
+                command_buffer,
+                vk::PipelineStageFlags::ALL_COMMANDS,
+                vk::PipelineStageFlags::TRANSFER,
+                vk::DependencyFlags::empty(),
+                memory_barriers,
+                &[],
+                &pre_images,
+            );
+        }
+
+        for copy in &self.pending {
+            match *copy {
+                PendingCopy::BufferUpload {
+                    staging_offset,
+                    dst,
+                    dst_offset,
+                    size,
+                    ..
+                } => self.device.cmd_copy_buffer(
+                    command_buffer,
+                    self.staging_buffer,
+                    dst,
+                    &[vk::BufferCopy {
+                        src_offset: staging_offset,
+                        dst_offset,
+                        size,
+                    }],
+                ),
+                PendingCopy::ImageUpload {
+                    staging_offset,
+                    transfer,
+                } => self.device.cmd_copy_buffer_to_image(
+                    command_buffer,
+                    self.staging_buffer,
+                    transfer.image,
+                    vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                    &[transfer.copy_region(staging_offset)],
+                ),
+                PendingCopy::BufferReadback {
+                    staging_offset,
+                    src,
+                    src_offset,
+                    size,
+                } => self.device.cmd_copy_buffer(
+                    command_buffer,
+                    src,
+                    self.staging_buffer,
+                    &[vk::BufferCopy {
+                        src_offset,
+                        dst_offset: staging_offset,
+                        size,
+                    }],
+                ),
+                PendingCopy::ImageReadback {
+                    staging_offset,
+                    transfer,
+                } => self.device.cmd_copy_image_to_buffer(
+                    command_buffer,
+                    transfer.image,
+                    vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    self.staging_buffer,
+                    &[transfer.copy_region(staging_offset)],
+                ),
+            }
+        }
+
+        let mut memory_barriers = Vec::new();
+        let mut dst_stage = vk::PipelineStageFlags::empty();
+        if has_uploads {
+            memory_barriers.push(
+                vk::MemoryBarrier::builder()
+                    .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+                    .dst_access_mask(vk::AccessFlags::MEMORY_READ | vk::AccessFlags::MEMORY_WRITE)
+                    .build(),
+            );
+            dst_stage |= vk::PipelineStageFlags::ALL_COMMANDS;
+        }
+        if has_readbacks {
+            memory_barriers.push(
+                vk::MemoryBarrier::builder()
+                    .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+                    .dst_access_mask(vk::AccessFlags::HOST_READ)
+                    .build(),
+            );
+            dst_stage |= vk::PipelineStageFlags::HOST;
+        }
+        if !post_images.is_empty() || !post_buffers.is_empty() {
+            dst_stage |= vk::PipelineStageFlags::ALL_COMMANDS;
+        }
+        if !dst_stage.is_empty() {
+            self.device.cmd_pipeline_barrier(
+                command_buffer,
+                vk::PipelineStageFlags::TRANSFER,
+                dst_stage,
+                vk::DependencyFlags::empty(),
+                &memory_barriers,
+                &post_buffers,
+                &post_images,
+            );
+        }
+    }
+
+    /// Records and submits all pending copies. Returns [`None`] if there was nothing to submit.
+    pub unsafe fn submit(&mut self) -> VkResult<Option<UploadSubmission>> {
+        if self.pending.is_empty() {
+            return Ok(None);
+        }
+
+        let command_buffer = match self.free_command_buffers.pop() {
+            Some(command_buffer) => command_buffer,
+            None => {
+                let allocate_info = vk::CommandBufferAllocateInfo::builder()
+                    .command_pool(self.command_pool)
+                    .level(vk::CommandBufferLevel::PRIMARY)
+                    .command_buffer_count(1);
+                self.device.allocate_command_buffers(&allocate_info)?[0]
+            }
+        };
+        let value = self.next_value;
+        let fence = if self.timeline == vk::Semaphore::null() {
+            match self.free_fences.pop() {
+                Some(fence) => fence,
+                None => match self
+                    .device
+                    .create_fence(&vk::FenceCreateInfo::default(), None)
+                {
+                    Ok(fence) => fence,
+                    Err(err) => {
+                        self.free_command_buffers.push(command_buffer);
+                        return Err(err);
+                    }
+                },
+            }
+        } else {
+            vk::Fence::null()
+        };
+
+        if let Err(err) = self.record_and_submit(command_buffer, fence, value) {
+            // Nothing was submitted, so both can be reused by the next attempt.
+            self.free_command_buffers.push(command_buffer);
+            if fence != vk::Fence::null() {
+                self.free_fences.push(fence);
+            }
+            return Err(err);
+        }
+
+        self.pending.clear();
+        self.in_flight.push_back(Batch {
+            value,
+            command_buffer,
+            fence,
+            ring_end: self.ring.head(),
+            readbacks: std::mem::take(&mut self.pending_readbacks),
+        });
+        self.next_value += 1;
+
+        Ok(Some(UploadSubmission {
+            semaphore: self.timeline,
+            fence,
+            value,
+        }))
+    }
+
+    unsafe fn record_and_submit(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        fence: vk::Fence,
+        value: u64,
+    ) -> VkResult<()> {
+        let begin_info = vk::CommandBufferBeginInfo::builder()
+            .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT);
+        self.device
+            .begin_command_buffer(command_buffer, &begin_info)?;
+        self.record(command_buffer);
+        self.device.end_command_buffer(command_buffer)?;
+
+        let command_buffers = [command_buffer];
+        let signal_semaphores = [self.timeline];
+        let signal_values = [value];
+        let mut timeline_info =
+            vk::TimelineSemaphoreSubmitInfo::builder().signal_semaphore_values(&signal_values);
+        let mut submit_info = vk::SubmitInfo::builder().command_buffers(&command_buffers);
+        if self.timeline != vk::Semaphore::null() {
+            submit_info = submit_info
+                .signal_semaphores(&signal_semaphores)
+                .push_next(&mut timeline_info);
+        }
+        self.device
+            .queue_submit(self.queue, &[submit_info.build()], fence)
+    }
+
+    /// Polls the device for completed submissions and recycles their resources.
+    unsafe fn retire(&mut self) -> VkResult<()> {
+        if self.timeline != vk::Semaphore::null() {
+            self.completed_value = self.device.get_semaphore_counter_value(self.timeline)?;
+        }
+        while let Some(batch) = self.in_flight.front() {
+            if self.timeline == vk::Semaphore::null() {
+                if !self.device.get_fence_status(batch.fence)? {
+                    break;
+                }
+                self.completed_value = batch.value;
+            } else if batch.value > self.completed_value {
+                break;
+            }
+
+            let batch = self.in_flight.pop_front().unwrap();
+            let mapped = self
+                .staging
+                .mapped_ptr()
+                .expect("staging memory is not host-visible")
+                .as_ptr() as *const u8;
+            for readback in batch.readbacks {
+                let data = std::slice::from_raw_parts(
+                    mapped.add(readback.staging_offset as usize),
+                    readback.size as usize,
+                );
+                self.completed_readbacks.insert(readback.id, data.to_vec());
+            }
+            if batch.fence != vk::Fence::null() {
+                self.device.reset_fences(&[batch.fence])?;
+                self.free_fences.push(batch.fence);
+            }
+            self.free_command_buffers.push(batch.command_buffer);
+            self.ring.release(batch.ring_end);
+        }
+        if self.in_flight.is_empty() && self.pending.is_empty() {
+            self.ring.reset();
+        }
+        Ok(())
+    }
+
+    unsafe fn wait_value(&mut self, value: u64, timeout: u64) -> VkResult<()> {
+        if value <= self.completed_value {
+            return Ok(());
+        }
+        if self.timeline != vk::Semaphore::null() {
+            let semaphores = [self.timeline];
+            let values = [value];
+            let wait_info = vk::SemaphoreWaitInfo::builder()
+                .semaphores(&semaphores)
+                .values(&values);
+            self.device.wait_semaphores(&wait_info, timeout)?;
+        } else if let Some(batch) = self.in_flight.iter().find(|batch| batch.value == value) {
+            self.device.wait_for_fences(&[batch.fence], true, timeout)?;
+        }
+        self.retire()
+    }
+
+    /// Blocks until `submission` has completed or `timeout` nanoseconds have passed.
+    pub unsafe fn wait(&mut self, submission: &UploadSubmission, timeout: u64) -> VkResult<()> {
+        self.wait_value(submission.value, timeout)
+    }
+
+    /// Returns whether `submission` has completed, without blocking.
+    pub unsafe fn is_complete(&mut self, submission: &UploadSubmission) -> VkResult<bool> {
+        self.retire()?;
+        Ok(submission.value <= self.completed_value)
+    }
+
+    /// Waits for all submissions and destroys the uploader's resources. Pending copies that were
+    /// never submitted are discarded.
+    pub unsafe fn destroy(mut self, allocator: &mut Allocator) -> VkResult<()> {
+        if let Some(last) = self.in_flight.back() {
+            let value = last.value;
+            self.wait_value(value, u64::MAX)?;
+        }
+        for fence in self.free_fences.drain(..) {
+            self.device.destroy_fence(fence, None);
+        }
+        if self.timeline != vk::Semaphore::null() {
+            self.device.destroy_semaphore(self.timeline, None);
+        }
+        self.device.destroy_command_pool(self.command_pool, None);
+        self.device.destroy_buffer(self.staging_buffer, None);
+        allocator.free(self.staging);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_wraps_around() {
+        let mut ring = StagingRing::new(256);
+        assert_eq!(ring.try_reserve(100), Some(0));
+        let first_end = ring.head();
+        assert_eq!(ring.try_reserve(100), Some(112));
+        // Does not fit at the end, and the start is still in use.
+        assert_eq!(ring.try_reserve(50), None);
+        ring.release(first_end);
+        assert_eq!(ring.try_reserve(50), Some(0));
+        // Wrapped ranges must end strictly before the tail.
+        assert_eq!(ring.try_reserve(200), None);
+        assert_eq!(ring.try_reserve(36), None);
+        assert_eq!(ring.try_reserve(30), Some(64));
+    }
+
+    #[test]
+    fn ring_full() {
+        let mut ring = StagingRing::new(256);
+        assert_eq!(ring.try_reserve(256), Some(0));
+        assert_eq!(ring.try_reserve(1), None);
+        // Releasing everything lets the next reservation wrap to the start.
+        ring.release(ring.head());
+        assert_eq!(ring.try_reserve(1), Some(0));
+        ring.reset();
+        assert_eq!(ring.try_reserve(256), Some(0));
+    }
+
+    #[test]
+    fn ring_rejects_oversize() {
+        let ring = StagingRing::new(256);
+        assert!(ring.check_size(240).is_ok());
+        assert!(ring.check_size(241) == Err(vk::Result::ERROR_OUT_OF_DEVICE_MEMORY));
+    }
+}
@@ -0,0 +1,217 @@
+//! Configuring the validation layer at instance creation.
+//!
+//! [`ValidationLayerBuilder`] collects [`vk::ValidationFeatureEnableEXT`] and
+//! [`vk::ValidationFeatureDisableEXT`] lists and chains them onto a [`vk::InstanceCreateInfo`]
+//! through [`vk::ValidationFeaturesEXT`], after [`ValidationLayerBuilder::check_layer()`] has
+//! confirmed that the layer is installed.
+//!
+//! The headers of this release predate `VK_EXT_layer_settings`, so other layer settings have to
+//! reach the layer through the environment variables returned by
+//! [`ValidationLayerBuilder::setting_variables()`]. Validation results can be persisted between
+//! runs with [`ext::ValidationCache`].
+//!
+//! [`ext::ValidationCache`]: crate::extensions::ext::ValidationCache
+
+use crate::prelude::*;
+use crate::vk;
+use crate::Entry;
+use std::ffi::{CStr, CString};
+
+/// Name of the Khronos validation layer.
+pub fn khronos_validation_layer_name() -> &'static CStr {
+    unsafe { CStr::from_bytes_with_nul_unchecked(b"VK_LAYER_KHRONOS_validation\0") }
+}
+
+/// Environment variable read by `layer_name` for the setting `key`, for example
+/// `VK_KHRONOS_VALIDATION_VALIDATE_SYNC` for `validate_sync`.
+fn setting_variable(layer_name: &CStr, key: &str) -> String {
+    let layer_name = layer_name.to_string_lossy();
+    let prefix = layer_name.strip_prefix("VK_LAYER_").unwrap_or(&layer_name);
+    format!("VK_{}_{}", prefix, key).to_uppercase()
+}
+
+fn contains_layer(layers: &[vk::LayerProperties], layer_name: &CStr) -> bool {
+    layers
+        .iter()
+        .any(|layer| unsafe { CStr::from_ptr(layer.layer_name.as_ptr()) } == layer_name)
+}
+
+/// Validation features and settings for an instance.
+///
+/// ```no_run
+/// # use ash::{validation::ValidationLayerBuilder, vk, Entry};
+/// # fn example(entry: &Entry) -> ash::prelude::VkResult<()> {
+/// let validation = ValidationLayerBuilder::new()
+///     .enable(vk::ValidationFeatureEnableEXT::GPU_ASSISTED)
+///     .enable(vk::ValidationFeatureEnableEXT::BEST_PRACTICES)
+///     .setting("report_flags", "error,warn,perf");
+/// validation.check_layer(entry)?;
+/// // Before any other threads are started.
+/// for (variable, value) in validation.setting_variables() {
+///     std::env::set_var(variable, value);
+/// }
+///
+/// let layer_names = [validation.layer_name().as_ptr()];
+/// let mut validation_features = vk::ValidationFeaturesEXT::default();
+/// let create_info = validation.push_next(
+///     &mut validation_features,
+///     vk::InstanceCreateInfo::builder().enabled_layer_names(&layer_names),
+/// );
+/// let instance = unsafe { entry.create_instance(&create_info, None)? };
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct ValidationLayerBuilder {
+    layer_name: CString,
+    enabled_features: Vec<vk::ValidationFeatureEnableEXT>,
+    disabled_features: Vec<vk::ValidationFeatureDisableEXT>,
+    settings: Vec<(String, String)>,
+}
+
+impl Default for ValidationLayerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ValidationLayerBuilder {
+    /// Configures [`khronos_validation_layer_name()`] without any features or settings.
+    pub fn new() -> Self {
+        Self {
+            layer_name: khronos_validation_layer_name().to_owned(),
+            enabled_features: Vec::new(),
+            disabled_features: Vec::new(),
+            settings: Vec::new(),
+        }
+    }
+
+    /// Configures a different validation layer.
+    pub fn layer(mut self, layer_name: &CStr) -> Self {
+        self.layer_name = layer_name.to_owned();
+        self
+    }
+
+    pub fn enable(mut self, feature: vk::ValidationFeatureEnableEXT) -> Self {
+        if !self.enabled_features.contains(&feature) {
+            self.enabled_features.push(feature);
+        }
+        self
+    }
+
+    pub fn disable(mut self, feature: vk::ValidationFeatureDisableEXT) -> Self {
+        if !self.disabled_features.contains(&feature) {
+            self.disabled_features.push(feature);
+        }
+        self
+    }
+
+    /// Sets the layer setting `key`, replacing an earlier value. Lists are separated by commas.
+    pub fn setting(mut self, key: &str, value: impl Into<String>) -> Self {
+        let value = value.into();
+        match self.settings.iter_mut().find(|(k, _)| k == key) {
+            Some(setting) => setting.1 = value,
+            None => self.settings.push((key.to_owned(), value)),
+        }
+        self
+    }
+
+    pub fn layer_name(&self) -> &CStr {
+        &self.layer_name
+    }
+
+    pub fn enabled_features(&self) -> &[vk::ValidationFeatureEnableEXT] {
+        &self.enabled_features
+    }
+
+    pub fn disabled_features(&self) -> &[vk::ValidationFeatureDisableEXT] {
+        &self.disabled_features
+    }
+
+    /// `(key, value)` pairs of the layer settings.
+    pub fn settings(&self) -> &[(String, String)] {
+        &self.settings
+    }
+
+    /// Returns [`vk::Result::ERROR_LAYER_NOT_PRESENT`] when the layer is not reported by
+    /// [`Entry::enumerate_instance_layer_properties()`].
+    pub fn check_layer(&self, entry: &Entry) -> VkResult<()> {
+        if contains_layer(
+            &entry.enumerate_instance_layer_properties()?,
+            &self.layer_name,
+        ) {
+            Ok(())
+        } else {
+            Err(vk::Result::ERROR_LAYER_NOT_PRESENT)
+        }
+    }
+
+    /// `(variable, value)` pairs of the environment variables the layer reads the settings from.
+    /// They have to be set before the instance is created, and as setting environment variables
+    /// races with any other thread reading them, before other threads are started.
+    pub fn setting_variables(&self) -> Vec<(String, String)> {
+        self.settings
+            .iter()
+            .map(|(key, value)| (setting_variable(&self.layer_name, key), value.clone()))
+            .collect()
+    }
+
+    /// Fills `validation_features` with the enabled and disabled features and chains it onto
+    /// `create_info`. The layer itself still has to be listed in
+    /// [`vk::InstanceCreateInfo::pp_enabled_layer_names`].
+    pub fn push_next<'a>(
+        &'a self,
+        validation_features: &'a mut vk::ValidationFeaturesEXT,
+        create_info: vk::InstanceCreateInfoBuilder<'a>,
+    ) -> vk::InstanceCreateInfoBuilder<'a> {
+        *validation_features = vk::ValidationFeaturesEXT::builder()
+            .enabled_validation_features(&self.enabled_features)
+            .disabled_validation_features(&self.disabled_features)
+            .build();
+        create_info.push_next(validation_features)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layer_settings() {
+        let builder = ValidationLayerBuilder::new()
+            .enable(vk::ValidationFeatureEnableEXT::BEST_PRACTICES)
+            .enable(vk::ValidationFeatureEnableEXT::BEST_PRACTICES)
+            .setting("report_flags", "error")
+            .setting("report_flags", "error,warn");
+        assert_eq!(builder.enabled_features().len(), 1);
+        assert_eq!(
+            builder.settings(),
+            [("report_flags".to_owned(), "error,warn".to_owned())]
+        );
+        assert_eq!(
+            builder.setting_variables(),
+            [(
+                "VK_KHRONOS_VALIDATION_REPORT_FLAGS".to_owned(),
+                "error,warn".to_owned()
+            )]
+        );
+
+        let mut validation_features = vk::ValidationFeaturesEXT::default();
+        let create_info =
+            builder.push_next(&mut validation_features, vk::InstanceCreateInfo::builder());
+        assert!(!create_info.p_next.is_null());
+        assert_eq!(validation_features.enabled_validation_feature_count, 1);
+
+        let mut layer = vk::LayerProperties::default();
+        for (dst, &src) in layer
+            .layer_name
+            .iter_mut()
+            .zip(b"VK_LAYER_KHRONOS_validation")
+        {
+            *dst = src as _;
+        }
+        assert!(contains_layer(&[layer], khronos_validation_layer_name()));
+        assert!(!contains_layer(&[], khronos_validation_layer_name()));
+    }
+}
@@ -31,3 +31,179 @@ impl super::ColorComponentFlags {
     /// Contraction of [`Self::R`] | [`Self::G`] | [`Self::B`] | [`Self::A`]
     pub const RGBA: Self = Self(Self::R.0 | Self::G.0 | Self::B.0 | Self::A.0);
 }
+
+impl super::TransformMatrixKHR {
+    /// The identity transform.
+    pub const IDENTITY: Self = Self {
+        matrix: [
+            1.0, 0.0, 0.0, 0.0, //
+            0.0, 1.0, 0.0, 0.0, //
+            0.0, 0.0, 1.0, 0.0,
+        ],
+    };
+
+    /// Constructs the transform from the top three rows of a row-major affine 4x4 matrix.
+    pub fn from_rows(rows: [[f32; 4]; 3]) -> Self {
+        let mut matrix = [0.0; 12];
+        for (dst, row) in matrix.chunks_exact_mut(4).zip(&rows) {
+            dst.copy_from_slice(row);
+        }
+        Self { matrix }
+    }
+
+    /// Constructs the transform from the columns of an affine 4x4 matrix, as stored by most
+    /// column-major math libraries. The bottom row is ignored.
+    pub fn from_cols(cols: [[f32; 4]; 4]) -> Self {
+        let mut rows = [[0.0; 4]; 3];
+        for (r, row) in rows.iter_mut().enumerate() {
+            for (c, value) in row.iter_mut().enumerate() {
+                *value = cols[c][r];
+            }
+        }
+        Self::from_rows(rows)
+    }
+
+    /// Constructs a transform that only translates by `translation`.
+    pub fn from_translation(translation: [f32; 3]) -> Self {
+        let mut transform = Self::IDENTITY;
+        transform.matrix[3] = translation[0];
+        transform.matrix[7] = translation[1];
+        transform.matrix[11] = translation[2];
+        transform
+    }
+
+    /// Returns the three rows of the row-major 3x4 matrix.
+    pub fn rows(&self) -> [[f32; 4]; 3] {
+        let mut rows = [[0.0; 4]; 3];
+        for (row, src) in rows.iter_mut().zip(self.matrix.chunks_exact(4)) {
+            row.copy_from_slice(src);
+        }
+        rows
+    }
+}
+
+impl super::AccelerationStructureInstanceKHR {
+    /// Largest value of the 24-bit instance custom index.
+    pub const MAX_INSTANCE_CUSTOM_INDEX: u32 = 0x00ff_ffff;
+    /// Largest value of the 24-bit shader binding table record offset.
+    pub const MAX_SHADER_BINDING_TABLE_RECORD_OFFSET: u32 = 0x00ff_ffff;
+
+    /// Constructs an instance of the bottom-level acceleration structure at
+    /// `acceleration_structure_address`, as returned by
+    /// [`AccelerationStructure::get_acceleration_structure_device_address()`][crate::extensions::khr::AccelerationStructure::get_acceleration_structure_device_address()].
+    ///
+    /// # Panics
+    ///
+    /// Panics when `instance_custom_index` or `shader_binding_table_record_offset` do not fit in
+    /// 24 bits, or `flags` does not fit in 8 bits.
+    pub fn new(
+        transform: super::TransformMatrixKHR,
+        instance_custom_index: u32,
+        mask: u8,
+        shader_binding_table_record_offset: u32,
+        flags: super::GeometryInstanceFlagsKHR,
+        acceleration_structure_address: super::DeviceAddress,
+    ) -> Self {
+        let mut instance = Self {
+            transform,
+            instance_custom_index_and_mask: Packed24_8::new(0, mask),
+            instance_shader_binding_table_record_offset_and_flags: Packed24_8::new(0, 0),
+            acceleration_structure_reference: super::AccelerationStructureReferenceKHR {
+                device_handle: acceleration_structure_address,
+            },
+        };
+        instance.set_instance_custom_index(instance_custom_index);
+        instance.set_shader_binding_table_record_offset(shader_binding_table_record_offset);
+        instance.set_flags(flags);
+        instance
+    }
+
+    /// Returns the 24-bit custom index exposed to shaders as `InstanceCustomIndexKHR`.
+    pub fn instance_custom_index(&self) -> u32 {
+        self.instance_custom_index_and_mask.low_24()
+    }
+
+    /// Sets the 24-bit custom index exposed to shaders as `InstanceCustomIndexKHR`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `index` exceeds [`Self::MAX_INSTANCE_CUSTOM_INDEX`].
+    pub fn set_instance_custom_index(&mut self, index: u32) {
+        assert!(
+            index <= Self::MAX_INSTANCE_CUSTOM_INDEX,
+            "instance custom index {} does not fit in 24 bits",
+            index
+        );
+        self.instance_custom_index_and_mask = Packed24_8::new(index, self.mask());
+    }
+
+    /// Returns the 8-bit visibility mask, which is tested against the cull mask of rays.
+    pub fn mask(&self) -> u8 {
+        self.instance_custom_index_and_mask.high_8()
+    }
+
+    /// Sets the 8-bit visibility mask.
+    pub fn set_mask(&mut self, mask: u8) {
+        self.instance_custom_index_and_mask = Packed24_8::new(self.instance_custom_index(), mask);
+    }
+
+    /// Returns the 24-bit offset added to the hit group index in the shader binding table.
+    pub fn shader_binding_table_record_offset(&self) -> u32 {
+        self.instance_shader_binding_table_record_offset_and_flags
+            .low_24()
+    }
+
+    /// Sets the 24-bit offset added to the hit group index in the shader binding table.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `offset` exceeds [`Self::MAX_SHADER_BINDING_TABLE_RECORD_OFFSET`].
+    pub fn set_shader_binding_table_record_offset(&mut self, offset: u32) {
+        assert!(
+            offset <= Self::MAX_SHADER_BINDING_TABLE_RECORD_OFFSET,
+            "shader binding table record offset {} does not fit in 24 bits",
+            offset
+        );
+        self.instance_shader_binding_table_record_offset_and_flags =
+            Packed24_8::new(offset, self.flags().as_raw() as u8);
+    }
+
+    /// Returns the instance flags, stored in 8 bits.
+    pub fn flags(&self) -> super::GeometryInstanceFlagsKHR {
+        super::GeometryInstanceFlagsKHR::from_raw(u32::from(
+            self.instance_shader_binding_table_record_offset_and_flags
+                .high_8(),
+        ))
+    }
+
+    /// Sets the instance flags, stored in 8 bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `flags` has bits set above the lowest 8.
+    pub fn set_flags(&mut self, flags: super::GeometryInstanceFlagsKHR) {
+        assert!(
+            flags.as_raw() <= u32::from(u8::MAX),
+            "geometry instance flags {:#x} do not fit in 8 bits",
+            flags.as_raw()
+        );
+        self.instance_shader_binding_table_record_offset_and_flags = Packed24_8::new(
+            self.shader_binding_table_record_offset(),
+            flags.as_raw() as u8,
+        );
+    }
+
+    /// Device address of the referenced acceleration structure, for instances built on the
+    /// device.
+    pub fn acceleration_structure_address(&self) -> super::DeviceAddress {
+        // Both union members are 64-bit values, so any bit pattern is a valid address.
+        unsafe { self.acceleration_structure_reference.device_handle }
+    }
+
+    /// Sets the device address of the referenced bottom-level acceleration structure.
+    pub fn set_acceleration_structure_address(&mut self, address: super::DeviceAddress) {
+        self.acceleration_structure_reference = super::AccelerationStructureReferenceKHR {
+            device_handle: address,
+        };
+    }
+}
@@ -0,0 +1,191 @@
+//! Presents directly to the first connected display through `VK_KHR_display`, without a
+//! windowing system, cycling the screen through a few colors.
+
+use ash::display::enumerate_display_targets;
+use ash::extensions::khr::{Display, Surface, Swapchain};
+use ash::swapchain::{SwapchainCreateDesc, SwapchainManager};
+use ash::{vk, Entry};
+use std::ffi::CStr;
+
+const FRAME_COUNT: usize = 600;
+
+fn main() {
+    unsafe {
+        let entry = Entry::linked();
+        let app_name = CStr::from_bytes_with_nul_unchecked(b"VulkanDisplay\0");
+        let appinfo = vk::ApplicationInfo::builder()
+            .application_name(app_name)
+            .application_version(0)
+            .engine_name(app_name)
+            .engine_version(0)
+            .api_version(vk::make_api_version(0, 1, 0, 0));
+        let extension_names = [Surface::name().as_ptr(), Display::name().as_ptr()];
+        let create_info = vk::InstanceCreateInfo::builder()
+            .application_info(&appinfo)
+            .enabled_extension_names(&extension_names);
+        let instance = entry
+            .create_instance(&create_info, None)
+            .expect("Instance creation error");
+        let display_loader = Display::new(&entry, &instance);
+        let surface_loader = Surface::new(&entry, &instance);
+
+        let (physical_device, target) = instance
+            .enumerate_physical_devices()
+            .expect("Physical device error")
+            .into_iter()
+            .find_map(|physical_device| {
+                enumerate_display_targets(&display_loader, physical_device)
+                    .ok()?
+                    .into_iter()
+                    .next()
+                    .map(|target| (physical_device, target))
+            })
+            .expect("Couldn't find a connected display.");
+        println!(
+            "Presenting at {}x{} @ {:.2} Hz",
+            target.extent().width,
+            target.extent().height,
+            target.refresh_rate() as f32 / 1000.0
+        );
+        let surface = target
+            .create_surface(&display_loader, None)
+            .expect("Display surface creation error");
+
+        let queue_family_index = instance
+            .get_physical_device_queue_family_properties(physical_device)
+            .iter()
+            .enumerate()
+            .position(|(index, info)| {
+                info.queue_flags.contains(vk::QueueFlags::GRAPHICS)
+                    && surface_loader
+                        .get_physical_device_surface_support(physical_device, index as u32, surface)
+                        .unwrap_or(false)
+            })
+            .expect("Couldn't find a queue family presenting to the display.")
+            as u32;
+        let priorities = [1.0];
+        let queue_info = [vk::DeviceQueueCreateInfo::builder()
+            .queue_family_index(queue_family_index)
+            .queue_priorities(&priorities)
+            .build()];
+        let device_extension_names = [Swapchain::name().as_ptr()];
+        let device_create_info = vk::DeviceCreateInfo::builder()
+            .queue_create_infos(&queue_info)
+            .enabled_extension_names(&device_extension_names);
+        let device = instance
+            .create_device(physical_device, &device_create_info, None)
+            .unwrap();
+        let queue = device.get_device_queue(queue_family_index, 0);
+
+        let mut swapchain = SwapchainManager::new(
+            &entry,
+            &instance,
+            &device,
+            SwapchainCreateDesc {
+                physical_device,
+                surface,
+                preferred_present_modes: vec![vk::PresentModeKHR::FIFO],
+                image_usage: vk::ImageUsageFlags::TRANSFER_DST,
+                ..Default::default()
+            },
+            target.extent(),
+        )
+        .expect("Swapchain creation error");
+
+        let pool_create_info = vk::CommandPoolCreateInfo::builder()
+            .flags(vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER)
+            .queue_family_index(queue_family_index);
+        let pool = device.create_command_pool(&pool_create_info, None).unwrap();
+        let command_buffer_allocate_info = vk::CommandBufferAllocateInfo::builder()
+            .command_pool(pool)
+            .command_buffer_count(swapchain.frames_in_flight() as u32)
+            .level(vk::CommandBufferLevel::PRIMARY);
+        let command_buffers = device
+            .allocate_command_buffers(&command_buffer_allocate_info)
+            .unwrap();
+
+        let colors = [
+            [1.0, 0.0, 0.0, 1.0],
+            [0.0, 1.0, 0.0, 1.0],
+            [0.0, 0.0, 1.0, 1.0],
+        ];
+        let range = vk::ImageSubresourceRange {
+            aspect_mask: vk::ImageAspectFlags::COLOR,
+            level_count: 1,
+            layer_count: 1,
+            ..Default::default()
+        };
+        for frame_number in 0..FRAME_COUNT {
+            let frame = swapchain
+                .acquire_frame(target.extent(), u64::MAX)
+                .expect("Acquire error");
+            let command_buffer = command_buffers[frame.frame_index];
+            device
+                .begin_command_buffer(
+                    command_buffer,
+                    &vk::CommandBufferBeginInfo::builder()
+                        .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT),
+                )
+                .unwrap();
+            let to_transfer = vk::ImageMemoryBarrier::builder()
+                .dst_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+                .old_layout(vk::ImageLayout::UNDEFINED)
+                .new_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+                .image(frame.image)
+                .subresource_range(range);
+            device.cmd_pipeline_barrier(
+                command_buffer,
+                vk::PipelineStageFlags::TRANSFER,
+                vk::PipelineStageFlags::TRANSFER,
+                vk::DependencyFlags::empty(),
+                &[],
+                &[],
+                &[*to_transfer],
+            );
+            device.cmd_clear_color_image(
+                command_buffer,
+                frame.image,
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                &vk::ClearColorValue {
+                    float32: colors[frame_number / 60 % colors.len()],
+                },
+                &[range],
+            );
+            let to_present = vk::ImageMemoryBarrier::builder()
+                .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+                .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+                .new_layout(vk::ImageLayout::PRESENT_SRC_KHR)
+                .image(frame.image)
+                .subresource_range(range);
+            device.cmd_pipeline_barrier(
+                command_buffer,
+                vk::PipelineStageFlags::TRANSFER,
+                vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+                vk::DependencyFlags::empty(),
+                &[],
+                &[],
+                &[*to_present],
+            );
+            device.end_command_buffer(command_buffer).unwrap();
+
+            let wait_semaphores = [frame.image_available];
+            let wait_stages = [vk::PipelineStageFlags::TRANSFER];
+            let signal_semaphores = [frame.render_finished];
+            let submit_info = vk::SubmitInfo::builder()
+                .wait_semaphores(&wait_semaphores)
+                .wait_dst_stage_mask(&wait_stages)
+                .command_buffers(std::slice::from_ref(&command_buffer))
+                .signal_semaphores(&signal_semaphores);
+            device
+                .queue_submit(queue, &[*submit_info], frame.fence)
+                .expect("Queue submit failed.");
+            swapchain.present(queue, &frame).expect("Present error");
+        }
+
+        swapchain.destroy().unwrap();
+        device.destroy_command_pool(pool, None);
+        device.destroy_device(None);
+        surface_loader.destroy_surface(surface, None);
+        instance.destroy_instance(None);
+    }
+}